@@ -0,0 +1,173 @@
+//! Throughput and hit-ratio benchmarks for [`EnhancedCache`].
+//!
+//! Modeled on the userspace-cache benchmarks embedded key/value stores ship:
+//! a fixed key space is hammered with a skewed (Zipfian) access distribution
+//! so that a small working set accounts for most reads, and each
+//! configuration reports wall-clock throughput alongside the *measured* hit
+//! ratio, predictive-hit count and total bytes resident. This turns the
+//! cache's many knobs — `max_size`, `ttl`, compression and eviction policy —
+//! into something that can be compared quantitatively rather than by
+//! eyeballing `EnhancedCacheStats`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lumascript::core::cache::arc::EvictionPolicy;
+use lumascript::core::cache::codec::Compression;
+use lumascript::core::cache::EnhancedCache;
+
+/// Total distinct keys the workload may reference.
+const KEY_SPACE: usize = 1_000_000;
+/// Zipf skew; 1.0 is the classic harmonic distribution.
+const ZIPF_SKEW: f64 = 1.0;
+/// Operations per benchmark iteration.
+const OPS_PER_ITER: usize = 10_000;
+
+/// A single benchmarked configuration.
+struct Workload {
+    label: &'static str,
+    max_size: usize,
+    ttl_seconds: u64,
+    compression: Compression,
+    policy: EvictionPolicy,
+    /// Fraction of `KEY_SPACE` that is "hot" — the working set the Zipf
+    /// distribution draws from.
+    working_set_fraction: f64,
+}
+
+/// Deterministic, dependency-free uniform generator so runs are reproducible
+/// without pulling in `rand`.
+struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_unit(&mut self) -> f64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.state >> 11) as f64) / ((1u64 << 53) as f64)
+    }
+}
+
+/// Precomputed Zipfian sampler over `[0, n)`.
+struct Zipf {
+    cdf: Vec<f64>,
+}
+
+impl Zipf {
+    fn new(n: usize, skew: f64) -> Self {
+        let mut cdf = Vec::with_capacity(n);
+        let mut acc = 0.0;
+        for rank in 1..=n {
+            acc += 1.0 / (rank as f64).powf(skew);
+            cdf.push(acc);
+        }
+        let total = acc;
+        for weight in &mut cdf {
+            *weight /= total;
+        }
+        Self { cdf }
+    }
+
+    /// Map a uniform `u` in `[0, 1)` to a rank index via binary search.
+    fn sample(&self, u: f64) -> usize {
+        match self.cdf.binary_search_by(|w| w.partial_cmp(&u).unwrap()) {
+            Ok(i) => i,
+            Err(i) => i.min(self.cdf.len() - 1),
+        }
+    }
+}
+
+/// Format a byte count as B/KiB/MiB/GiB: plain bytes as an integer, larger
+/// units with two decimals.
+fn human_readable_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{value:.2} {}", UNITS[unit])
+    }
+}
+
+/// Run `OPS_PER_ITER` get/insert cycles against `cache`, inserting on a miss.
+fn drive(cache: &EnhancedCache<String>, zipf: &Zipf, rng: &mut Lcg, working_set: usize) {
+    for _ in 0..OPS_PER_ITER {
+        let rank = zipf.sample(rng.next_unit());
+        let id = rank % working_set;
+        let key = format!("key:{id}");
+        if cache.get(&key).is_none() {
+            cache.insert(key, format!("value-for-key-{id:08}"));
+        }
+    }
+}
+
+fn bench_cache(c: &mut Criterion) {
+    let workloads = [
+        Workload {
+            label: "predictive/no-compression",
+            max_size: 50_000,
+            ttl_seconds: 60,
+            compression: Compression::None,
+            policy: EvictionPolicy::Predictive,
+            working_set_fraction: 0.05,
+        },
+        Workload {
+            label: "predictive/compressed",
+            max_size: 50_000,
+            ttl_seconds: 60,
+            compression: Compression::Fast,
+            policy: EvictionPolicy::Predictive,
+            working_set_fraction: 0.05,
+        },
+        Workload {
+            label: "arc/compressed",
+            max_size: 50_000,
+            ttl_seconds: 60,
+            compression: Compression::Fast,
+            policy: EvictionPolicy::Arc,
+            working_set_fraction: 0.05,
+        },
+    ];
+
+    let mut group = c.benchmark_group("enhanced_cache");
+    for w in &workloads {
+        let working_set = ((KEY_SPACE as f64) * w.working_set_fraction).max(1.0) as usize;
+        let zipf = Zipf::new(working_set, ZIPF_SKEW);
+
+        group.bench_function(w.label, |b| {
+            let cache = EnhancedCache::<String>::with_options(
+                w.max_size,
+                w.ttl_seconds,
+                w.compression,
+                w.policy,
+            );
+            let mut rng = Lcg::new(0x5eed_1234_abcd_ef01);
+            b.iter(|| drive(&cache, &zipf, &mut rng, working_set));
+
+            // Report the steady-state knobs outside criterion's timing loop.
+            let stats = cache.get_stats();
+            eprintln!(
+                "[{}] hit_ratio={:.3} predictive_hits={} stored={}",
+                w.label,
+                stats.hit_ratio,
+                stats.predictive_hits,
+                human_readable_bytes(stats.bytes_stored),
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_cache);
+criterion_main!(benches);
@@ -0,0 +1,220 @@
+//! A lint engine over the LumaScript AST.
+//!
+//! Rules implement the [`Rule`] trait and emit [`Diagnostic`]s; a
+//! diagnostic may carry a [`Fixer`] with byte-range text edits so the
+//! playground can offer autofixes. Rules are `Send + Sync` so they can be
+//! run in parallel with rayon (already a dependency).
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::parser::{Expr, Function, Program, Span, Stmt};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+    Hint,
+}
+
+/// A single text edit: replace the bytes in `range` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Edit {
+    pub range: Span,
+    pub replacement: String,
+}
+
+/// An optional autofix attached to a diagnostic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixer {
+    pub edits: Vec<Edit>,
+}
+
+/// A reported problem, optionally fixable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    pub fix: Option<Fixer>,
+}
+
+/// Context handed to each rule as it runs.
+pub struct LintContext<'a> {
+    pub source: &'a str,
+}
+
+/// A lint rule. Implementations inspect `program` and push diagnostics.
+pub trait Rule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn check(&self, program: &Program, ctx: &LintContext) -> Vec<Diagnostic>;
+}
+
+/// The set of rules to run. Defaults to the starter rules.
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Linter {
+    /// A linter with the built-in starter rules.
+    pub fn new() -> Self {
+        Self {
+            rules: vec![
+                Box::new(UnusedVariable),
+                Box::new(UnreachableCode),
+                Box::new(EmptyFunctionBody),
+            ],
+        }
+    }
+
+    pub fn with_rule(mut self, rule: Box<dyn Rule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Run every rule in parallel and collect all diagnostics.
+    pub fn run(&self, program: &Program, source: &str) -> Vec<Diagnostic> {
+        let ctx = LintContext { source };
+        self.rules
+            .par_iter()
+            .flat_map(|rule| rule.check(program, &ctx))
+            .collect()
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apply the fixes from `diagnostics` to `source`, keeping only
+/// non-overlapping edits sorted by start offset (a later edit that would
+/// overlap an already-applied one is skipped).
+pub fn apply_fixes(source: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut edits: Vec<&Edit> = diagnostics
+        .iter()
+        .filter_map(|d| d.fix.as_ref())
+        .flat_map(|f| f.edits.iter())
+        .collect();
+    edits.sort_by_key(|e| e.range.start);
+
+    let mut result = String::with_capacity(source.len());
+    let mut cursor = 0usize;
+    for edit in edits {
+        if edit.range.start < cursor || edit.range.end > source.len() {
+            // Overlaps an already-applied edit (or is out of bounds): skip.
+            continue;
+        }
+        result.push_str(&source[cursor..edit.range.start]);
+        result.push_str(&edit.replacement);
+        cursor = edit.range.end;
+    }
+    result.push_str(&source[cursor..]);
+    result
+}
+
+// ---- Starter rules -------------------------------------------------------
+
+/// Warn about `let` bindings that are never referenced in their function.
+struct UnusedVariable;
+
+impl Rule for UnusedVariable {
+    fn name(&self) -> &'static str {
+        "unused-variable"
+    }
+
+    fn check(&self, program: &Program, _ctx: &LintContext) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for func in &program.functions {
+            for (idx, stmt) in func.body.iter().enumerate() {
+                if let Stmt::Let { name, span, .. } = stmt {
+                    if !referenced_after(&func.body[idx + 1..], name) {
+                        out.push(Diagnostic {
+                            severity: Severity::Warning,
+                            span: *span,
+                            message: format!("unused variable `{name}`"),
+                            fix: None,
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Flag statements that can never run because a `return` precedes them, and
+/// offer a fix that deletes the dead tail.
+struct UnreachableCode;
+
+impl Rule for UnreachableCode {
+    fn name(&self) -> &'static str {
+        "unreachable-code"
+    }
+
+    fn check(&self, program: &Program, _ctx: &LintContext) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for func in &program.functions {
+            if let Some(pos) = func.body.iter().position(|s| matches!(s, Stmt::Return { .. })) {
+                if pos + 1 < func.body.len() {
+                    let dead_start = func.body[pos + 1].span().start;
+                    let dead_end = func.body.last().unwrap().span().end;
+                    out.push(Diagnostic {
+                        severity: Severity::Warning,
+                        span: Span::new(dead_start, dead_end),
+                        message: "unreachable code after `return`".to_string(),
+                        fix: Some(Fixer {
+                            edits: vec![Edit {
+                                range: Span::new(dead_start, dead_end),
+                                replacement: String::new(),
+                            }],
+                        }),
+                    });
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Hint that a function has an empty body.
+struct EmptyFunctionBody;
+
+impl Rule for EmptyFunctionBody {
+    fn name(&self) -> &'static str {
+        "empty-function-body"
+    }
+
+    fn check(&self, program: &Program, _ctx: &LintContext) -> Vec<Diagnostic> {
+        program
+            .functions
+            .iter()
+            .filter(|f| f.body.is_empty())
+            .map(|f: &Function| Diagnostic {
+                severity: Severity::Hint,
+                span: f.span,
+                message: format!("function `{}` has an empty body", f.name),
+                fix: None,
+            })
+            .collect()
+    }
+}
+
+/// True if `name` is referenced anywhere in the given statements.
+fn referenced_after(stmts: &[Stmt], name: &str) -> bool {
+    stmts.iter().any(|s| match s {
+        Stmt::Let { value, .. } | Stmt::Expr { expr: value, .. } => expr_uses(value, name),
+        Stmt::Return { value: Some(expr), .. } => expr_uses(expr, name),
+        Stmt::Return { value: None, .. } => false,
+    })
+}
+
+fn expr_uses(expr: &Expr, name: &str) -> bool {
+    match expr {
+        Expr::Ident { name: n, .. } => n == name,
+        Expr::Call { args, .. } => args.iter().any(|a| expr_uses(a, name)),
+        Expr::Number { .. } | Expr::StringLit { .. } => false,
+    }
+}
@@ -0,0 +1,225 @@
+//! Runtime values for LumaScript and the coercion layer between them.
+//!
+//! Scripts work over a small set of dynamically typed [`Value`]s and
+//! frequently need to move between those types — reading a string from
+//! input and treating it as an integer, stamping a value as a timestamp,
+//! and so on. Rather than scatter ad-hoc `as` casts and `parse()` calls
+//! through every place that needs a coercion, they go through a single
+//! [`Conversion`], parsed by name via [`FromStr`], and applied with
+//! [`Value::convert`]. Every failure surfaces as a structured [`ConvError`]
+//! instead of a panic.
+//!
+//! This module is the shared value vocabulary: a `convert(value, "type")`
+//! builtin can dispatch straight to [`Value::convert`] so there is a single
+//! coercion implementation rather than one per caller.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// A dynamically typed LumaScript value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Bytes(Vec<u8>),
+    Timestamp(DateTime<Utc>),
+}
+
+/// A coercion from one [`Value`] to another, identified by name.
+///
+/// Parse one with [`FromStr`]:
+///
+/// ```ignore
+/// let c: Conversion = "int".parse()?;
+/// let c: Conversion = "timestamp_fmt:%Y-%m-%d".parse()?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    Int,
+    Float,
+    Bool,
+    /// Leave the value as a string (stringifying non-strings).
+    Str,
+    /// Leave the value as raw bytes (encoding non-bytes as UTF-8).
+    Bytes,
+    /// Parse strings as RFC 3339; integers and floats are epoch milliseconds.
+    Timestamp,
+    /// Parse strings with a custom `chrono` strftime pattern; a date-only
+    /// pattern yields midnight UTC.
+    TimestampFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = ConvError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(pattern) = s.strip_prefix("timestamp_fmt:") {
+            return Ok(Conversion::TimestampFmt(pattern.to_string()));
+        }
+        match s {
+            "int" | "integer" => Ok(Conversion::Int),
+            "float" => Ok(Conversion::Float),
+            "bool" | "boolean" => Ok(Conversion::Bool),
+            "string" => Ok(Conversion::Str),
+            "bytes" => Ok(Conversion::Bytes),
+            "timestamp" => Ok(Conversion::Timestamp),
+            other => Err(ConvError::UnknownConversion(other.to_string())),
+        }
+    }
+}
+
+/// A coercion that could not be performed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvError {
+    /// The conversion name was not recognised.
+    UnknownConversion(String),
+    /// The value could not be parsed into the requested type.
+    Unparseable { value: String, target: &'static str },
+}
+
+impl std::fmt::Display for ConvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvError::UnknownConversion(name) => write!(f, "unknown conversion `{name}`"),
+            ConvError::Unparseable { value, target } => {
+                write!(f, "cannot coerce `{value}` to {target}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConvError {}
+
+impl Value {
+    /// Coerce this value according to `conv`, returning a structured error
+    /// for unparseable input rather than panicking.
+    pub fn convert(&self, conv: &Conversion) -> Result<Value, ConvError> {
+        match conv {
+            Conversion::Int => self.to_int().map(Value::Int),
+            Conversion::Float => self.to_float().map(Value::Float),
+            Conversion::Bool => self.to_bool().map(Value::Bool),
+            Conversion::Str => Ok(Value::Str(self.to_display_string())),
+            Conversion::Bytes => Ok(Value::Bytes(self.to_bytes())),
+            Conversion::Timestamp => self.to_timestamp(None).map(Value::Timestamp),
+            Conversion::TimestampFmt(pattern) => {
+                self.to_timestamp(Some(pattern)).map(Value::Timestamp)
+            }
+        }
+    }
+
+    fn to_int(&self) -> Result<i64, ConvError> {
+        match self {
+            Value::Int(v) => Ok(*v),
+            Value::Float(v) => Ok(*v as i64),
+            Value::Bool(v) => Ok(*v as i64),
+            Value::Timestamp(t) => Ok(t.timestamp_millis()),
+            Value::Str(s) => s.trim().parse::<i64>().map_err(|_| ConvError::Unparseable {
+                value: s.clone(),
+                target: "int",
+            }),
+            Value::Bytes(_) => Err(ConvError::Unparseable {
+                value: self.to_display_string(),
+                target: "int",
+            }),
+        }
+    }
+
+    fn to_float(&self) -> Result<f64, ConvError> {
+        match self {
+            Value::Int(v) => Ok(*v as f64),
+            Value::Float(v) => Ok(*v),
+            Value::Bool(v) => Ok(*v as i64 as f64),
+            Value::Str(s) => s.trim().parse::<f64>().map_err(|_| ConvError::Unparseable {
+                value: s.clone(),
+                target: "float",
+            }),
+            _ => Err(ConvError::Unparseable {
+                value: self.to_display_string(),
+                target: "float",
+            }),
+        }
+    }
+
+    fn to_bool(&self) -> Result<bool, ConvError> {
+        match self {
+            Value::Bool(v) => Ok(*v),
+            Value::Int(v) => Ok(*v != 0),
+            Value::Float(v) => Ok(*v != 0.0),
+            Value::Str(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => Ok(true),
+                "false" | "0" | "no" => Ok(false),
+                _ => Err(ConvError::Unparseable {
+                    value: s.clone(),
+                    target: "bool",
+                }),
+            },
+            _ => Err(ConvError::Unparseable {
+                value: self.to_display_string(),
+                target: "bool",
+            }),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Value::Bytes(b) => b.clone(),
+            other => other.to_display_string().into_bytes(),
+        }
+    }
+
+    fn to_timestamp(&self, pattern: Option<&str>) -> Result<DateTime<Utc>, ConvError> {
+        match self {
+            Value::Timestamp(t) => Ok(*t),
+            // Integers and floats are interpreted as epoch milliseconds.
+            Value::Int(v) => Utc.timestamp_millis_opt(*v).single().ok_or_else(|| {
+                ConvError::Unparseable {
+                    value: v.to_string(),
+                    target: "timestamp",
+                }
+            }),
+            Value::Float(v) => Utc
+                .timestamp_millis_opt(*v as i64)
+                .single()
+                .ok_or_else(|| ConvError::Unparseable {
+                    value: v.to_string(),
+                    target: "timestamp",
+                }),
+            Value::Str(s) => match pattern {
+                Some(fmt) => NaiveDateTime::parse_from_str(s, fmt)
+                    .or_else(|_| {
+                        NaiveDate::parse_from_str(s, fmt)
+                            .map(|date| date.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+                    })
+                    .map(|naive| Utc.from_utc_datetime(&naive))
+                    .map_err(|_| ConvError::Unparseable {
+                        value: s.clone(),
+                        target: "timestamp",
+                    }),
+                None => DateTime::parse_from_rfc3339(s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| ConvError::Unparseable {
+                        value: s.clone(),
+                        target: "timestamp",
+                    }),
+            },
+            Value::Bytes(_) => Err(ConvError::Unparseable {
+                value: self.to_display_string(),
+                target: "timestamp",
+            }),
+        }
+    }
+
+    fn to_display_string(&self) -> String {
+        match self {
+            Value::Int(v) => v.to_string(),
+            Value::Float(v) => v.to_string(),
+            Value::Bool(v) => v.to_string(),
+            Value::Str(s) => s.clone(),
+            Value::Bytes(b) => String::from_utf8_lossy(b).into_owned(),
+            Value::Timestamp(t) => t.to_rfc3339(),
+        }
+    }
+}
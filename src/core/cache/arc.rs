@@ -0,0 +1,159 @@
+//! Adaptive Replacement Cache (ARC) bookkeeping.
+//!
+//! ARC keeps four ordered lists over a capacity `c`: `T1` (resident, seen
+//! once), `T2` (resident, seen ≥2 times) and the ghost lists `B1`/`B2`,
+//! which hold only the keys of recently evicted `T1`/`T2` entries. A
+//! tunable target `p` splits capacity between `T1` and `T2` and is nudged
+//! on ghost hits so the policy adapts between recency and frequency.
+//!
+//! This module tracks keys only; the resident value store lives in
+//! [`EnhancedCache`](super::EnhancedCache). [`ArcPolicy::reference`]
+//! returns the keys that must be dropped from the value store so the two
+//! stay in sync; [`ArcPolicy::touch`] never evicts and returns nothing.
+
+use std::collections::VecDeque;
+
+/// Which eviction policy an [`EnhancedCache`](super::EnhancedCache) uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// The original prediction-driven eviction.
+    Predictive,
+    /// Adaptive Replacement Cache.
+    Arc,
+}
+
+/// ARC list bookkeeping. LRU is the front of each deque, MRU the back.
+#[derive(Debug)]
+pub struct ArcPolicy {
+    c: usize,
+    p: usize,
+    t1: VecDeque<String>,
+    t2: VecDeque<String>,
+    b1: VecDeque<String>,
+    b2: VecDeque<String>,
+}
+
+impl ArcPolicy {
+    pub fn new(c: usize) -> Self {
+        Self {
+            c: c.max(1),
+            p: 0,
+            t1: VecDeque::new(),
+            t2: VecDeque::new(),
+            b1: VecDeque::new(),
+            b2: VecDeque::new(),
+        }
+    }
+
+    /// Reference `key` (on insert or a fresh request), returning any
+    /// resident keys that were evicted as a result.
+    pub fn reference(&mut self, key: &str) -> Vec<String> {
+        let mut evicted = Vec::new();
+
+        // Case: hit on a resident key — promote to T2's MRU.
+        if remove_from(&mut self.t1, key) || remove_from(&mut self.t2, key) {
+            self.t2.push_back(key.to_string());
+            return evicted;
+        }
+
+        // Case: hit in ghost B1 — bias toward recency. The `p` step uses the
+        // list sizes at the moment of the hit, so snapshot them before the
+        // ghost key is removed.
+        {
+            let (b1_len, b2_len) = (self.b1.len(), self.b2.len());
+            if remove_from(&mut self.b1, key) {
+                let delta = (b2_len / b1_len.max(1)).max(1);
+                self.p = (self.p + delta).min(self.c);
+                self.replace(key, &mut evicted);
+                self.t2.push_back(key.to_string());
+                return evicted;
+            }
+        }
+
+        // Case: hit in ghost B2 — bias toward frequency. Same snapshot rule.
+        {
+            let (b1_len, b2_len) = (self.b1.len(), self.b2.len());
+            if remove_from(&mut self.b2, key) {
+                let delta = (b1_len / b2_len.max(1)).max(1);
+                self.p = self.p.saturating_sub(delta);
+                self.replace(key, &mut evicted);
+                self.t2.push_back(key.to_string());
+                return evicted;
+            }
+        }
+
+        // Case: full miss — make room, then insert at T1's MRU.
+        if self.t1.len() + self.b1.len() == self.c {
+            if self.b1.is_empty() {
+                // B1 empty: evict the LRU of T1 outright.
+                if let Some(old) = self.t1.pop_front() {
+                    evicted.push(old);
+                }
+            } else {
+                self.b1.pop_front();
+                self.replace(key, &mut evicted);
+            }
+        } else {
+            let total = self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len();
+            if total >= self.c {
+                if total >= 2 * self.c {
+                    self.b2.pop_front();
+                }
+                self.replace(key, &mut evicted);
+            }
+        }
+        self.t1.push_back(key.to_string());
+        evicted
+    }
+
+    /// A plain resident hit: promote `key` to T2's MRU without admitting
+    /// anything new. Returns nothing to evict.
+    pub fn touch(&mut self, key: &str) {
+        if remove_from(&mut self.t1, key) || remove_from(&mut self.t2, key) {
+            self.t2.push_back(key.to_string());
+        }
+    }
+
+    /// The REPLACE subroutine: move an LRU resident entry to the matching
+    /// ghost list, recording it as evicted from the value store.
+    fn replace(&mut self, incoming: &str, evicted: &mut Vec<String>) {
+        let incoming_in_b2 = self.b2.iter().any(|k| k == incoming);
+        let evict_from_t1 = !self.t1.is_empty()
+            && (self.t1.len() > self.p || (incoming_in_b2 && self.t1.len() == self.p));
+
+        if evict_from_t1 {
+            if let Some(old) = self.t1.pop_front() {
+                evicted.push(old.clone());
+                self.b1.push_back(old);
+            }
+        } else if let Some(old) = self.t2.pop_front() {
+            evicted.push(old.clone());
+            self.b2.push_back(old);
+        }
+    }
+
+    pub fn target_p(&self) -> usize {
+        self.p
+    }
+    pub fn t1_len(&self) -> usize {
+        self.t1.len()
+    }
+    pub fn t2_len(&self) -> usize {
+        self.t2.len()
+    }
+    pub fn b1_len(&self) -> usize {
+        self.b1.len()
+    }
+    pub fn b2_len(&self) -> usize {
+        self.b2.len()
+    }
+}
+
+fn remove_from(list: &mut VecDeque<String>, key: &str) -> bool {
+    if let Some(pos) = list.iter().position(|k| k == key) {
+        list.remove(pos);
+        true
+    } else {
+        false
+    }
+}
@@ -0,0 +1,78 @@
+//! Pluggable value compression for [`EnhancedCache`](super::EnhancedCache).
+//!
+//! Values are serialized with serde and then run through an LZ-family
+//! compressor before being stored as byte blobs, so the cache holds the
+//! compressed representation and reports honest byte sizes.
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression as FlateLevel;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+
+/// Compression level to apply to stored values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Store the serialized bytes uncompressed.
+    None,
+    /// Favor speed over ratio.
+    Fast,
+    /// Favor ratio over speed.
+    Best,
+}
+
+impl Compression {
+    fn level(self) -> Option<FlateLevel> {
+        match self {
+            Compression::None => None,
+            Compression::Fast => Some(FlateLevel::fast()),
+            Compression::Best => Some(FlateLevel::best()),
+        }
+    }
+}
+
+/// Encode/decode between a value and its stored byte representation.
+pub trait CacheCodec: Send + Sync {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String>;
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String>;
+}
+
+/// The default codec: serde-JSON serialization followed by zlib at the
+/// configured [`Compression`] level.
+pub struct ZlibCodec {
+    compression: Compression,
+}
+
+impl ZlibCodec {
+    pub fn new(compression: Compression) -> Self {
+        Self { compression }
+    }
+}
+
+impl CacheCodec for ZlibCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        let json = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+        match self.compression.level() {
+            None => Ok(json),
+            Some(level) => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), level);
+                encoder.write_all(&json).map_err(|e| e.to_string())?;
+                encoder.finish().map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+        let json = match self.compression.level() {
+            None => bytes.to_vec(),
+            Some(_) => {
+                let mut decoder = ZlibDecoder::new(bytes);
+                let mut out = Vec::new();
+                decoder.read_to_end(&mut out).map_err(|e| e.to_string())?;
+                out
+            }
+        };
+        serde_json::from_slice(&json).map_err(|e| e.to_string())
+    }
+}
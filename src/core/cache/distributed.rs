@@ -1,9 +1,14 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use super::recovery::{RecoveryManager, RecoveryStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheNode {
@@ -11,10 +16,11 @@ pub struct CacheNode {
     address: String,
     port: u16,
     status: NodeStatus,
+    #[serde(skip, default = "Instant::now")]
     last_heartbeat: Instant,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum NodeStatus {
     Active,
     Syncing,
@@ -22,14 +28,101 @@ pub enum NodeStatus {
     Failed,
 }
 
+/// Wire messages exchanged between nodes, each serialized behind a
+/// big-endian `u32` length prefix. Heartbeats travel as
+/// [`HeartbeatMessage`] frames on the same connections.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SyncMessage<T> {
+    Replicate { key: String, value: T, version: u64 },
+    Invalidate { key: String },
+    RecoveryRequest { node_id: String },
+    /// Anti-entropy: "here is my per-key version summary, tell me what differs."
+    SummaryRequest { node_id: String, summary: VersionSummary },
+    /// The differing keys the peer should pull back.
+    PullKeys { keys: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatMessage {
+    node_id: String,
+    /// Epoch-millis; `Instant` is not meaningfully serializable across hosts.
+    timestamp_ms: u64,
+}
+
+/// A per-key version map plus a rolling hash over it, used as a cheap
+/// Merkle-style summary so two nodes can detect divergence without
+/// shipping the whole keyspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionSummary {
+    versions: HashMap<String, u64>,
+}
+
+impl VersionSummary {
+    /// Keys whose version in `self` differs from (or is missing in) `other`.
+    fn diff(&self, other: &VersionSummary) -> Vec<String> {
+        self.versions
+            .iter()
+            .filter(|(k, v)| other.versions.get(*k) != Some(*v))
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Order-independent digest of the summary; equal digests mean the two
+    /// nodes hold the same key/version set.
+    fn root(&self) -> u64 {
+        self.versions
+            .iter()
+            .fold(0u64, |acc, (k, v)| acc ^ hash_kv(k, *v))
+    }
+}
+
+fn hash_kv(key: &str, version: u64) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut h = DefaultHasher::new();
+    key.hash(&mut h);
+    version.hash(&mut h);
+    h.finish()
+}
+
+/// A pool of persistent connections keyed by node id, so we stop opening a
+/// fresh `TcpStream` per message.
+#[derive(Default)]
+struct ConnectionPool {
+    streams: HashMap<String, Arc<Mutex<TcpStream>>>,
+}
+
+impl ConnectionPool {
+    /// Get the live connection to `node`, dialing and caching it on first use.
+    async fn connect(&mut self, node: &CacheNode) -> std::io::Result<Arc<Mutex<TcpStream>>> {
+        if let Some(stream) = self.streams.get(&node.id) {
+            return Ok(stream.clone());
+        }
+        let stream = TcpStream::connect(format!("{}:{}", node.address, node.port)).await?;
+        let shared = Arc::new(Mutex::new(stream));
+        self.streams.insert(node.id.clone(), shared.clone());
+        Ok(shared)
+    }
+
+    /// Drop a connection so the next send redials (e.g. after a write error).
+    fn drop_node(&mut self, node_id: &str) {
+        self.streams.remove(node_id);
+    }
+}
+
 pub struct DistributedCache<T> {
     nodes: Arc<RwLock<HashMap<String, CacheNode>>>,
+    store: Arc<RwLock<HashMap<String, (T, u64)>>>,
     local_node: CacheNode,
-    sync_channel: mpsc::Sender<SyncMessage>,
-    recovery_manager: Arc<RecoveryManager>,
+    pool: Arc<Mutex<ConnectionPool>>,
+    sync_channel: mpsc::Sender<SyncMessage<T>>,
+    recovery_manager: Arc<Mutex<RecoveryManager>>,
 }
 
-impl<T: Clone + Send + Sync> DistributedCache<T> {
+impl<T> DistributedCache<T>
+where
+    T: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
     pub async fn new(local_address: String, port: u16) -> Self {
         let local_node = CacheNode {
             id: uuid::Uuid::new_v4().to_string(),
@@ -40,24 +133,39 @@ impl<T: Clone + Send + Sync> DistributedCache<T> {
         };
 
         let (sync_tx, sync_rx) = mpsc::channel(100);
-        let recovery_manager = Arc::new(RecoveryManager::new());
+        let recovery_manager = Arc::new(Mutex::new(RecoveryManager::new()));
 
         let cache = Self {
             nodes: Arc::new(RwLock::new(HashMap::new())),
+            store: Arc::new(RwLock::new(HashMap::new())),
             local_node: local_node.clone(),
+            pool: Arc::new(Mutex::new(ConnectionPool::default())),
             sync_channel: sync_tx,
             recovery_manager: recovery_manager.clone(),
         };
 
         // Start background tasks
-        tokio::spawn(cache.start_heartbeat());
-        tokio::spawn(cache.handle_sync_messages(sync_rx));
-        tokio::spawn(cache.monitor_nodes());
+        tokio::spawn(cache.clone_handle().start_server());
+        tokio::spawn(cache.clone_handle().start_heartbeat());
+        tokio::spawn(cache.clone_handle().handle_sync_messages(sync_rx));
+        tokio::spawn(cache.clone_handle().monitor_nodes());
 
         cache
     }
 
-    async fn start_heartbeat(&self) {
+    /// A cheap shared handle for background tasks; all fields are `Arc`.
+    fn clone_handle(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            store: self.store.clone(),
+            local_node: self.local_node.clone(),
+            pool: self.pool.clone(),
+            sync_channel: self.sync_channel.clone(),
+            recovery_manager: self.recovery_manager.clone(),
+        }
+    }
+
+    async fn start_heartbeat(self) {
         let mut interval = tokio::time::interval(Duration::from_secs(5));
         loop {
             interval.tick().await;
@@ -68,61 +176,257 @@ impl<T: Clone + Send + Sync> DistributedCache<T> {
     async fn broadcast_heartbeat(&self) {
         let heartbeat = HeartbeatMessage {
             node_id: self.local_node.id.clone(),
-            timestamp: Instant::now(),
+            timestamp_ms: now_millis(),
+        };
+        let payload = match serde_json::to_vec(&heartbeat) {
+            Ok(p) => p,
+            Err(_) => return,
         };
 
-        let nodes = self.nodes.read().await;
-        for node in nodes.values() {
-            if let Ok(mut stream) = TcpStream::connect(format!("{}:{}", node.address, node.port)).await {
-                if let Ok(_) = serde_json::to_writer(&mut stream, &heartbeat) {
-                    // Heartbeat sent successfully
+        let nodes: Vec<CacheNode> = self.nodes.read().await.values().cloned().collect();
+        for node in nodes {
+            self.send_framed(&node, &payload).await;
+        }
+    }
+
+    /// Accept inbound connections and read their frames. This is the server
+    /// side of the protocol the pooled `send_framed` writes to: without it
+    /// the `SummaryRequest`/`PullKeys`/`Replicate` frames peers send would
+    /// never be read, so the cluster could never converge.
+    async fn start_server(self) {
+        let listener = match TcpListener::bind(format!(
+            "{}:{}",
+            self.local_node.address, self.local_node.port
+        ))
+        .await
+        {
+            Ok(listener) => listener,
+            Err(_) => return,
+        };
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(self.clone_handle().handle_connection(stream));
                 }
+                Err(_) => continue,
             }
         }
     }
 
-    async fn handle_sync_messages(&self, mut rx: mpsc::Receiver<SyncMessage>) {
+    /// Read framed messages off one connection until it closes, feeding sync
+    /// messages into the same channel the background worker drains and
+    /// applying heartbeats directly.
+    async fn handle_connection(self, mut stream: TcpStream) {
+        loop {
+            let frame = match read_frame(&mut stream).await {
+                Ok(frame) => frame,
+                Err(_) => break, // peer closed or the stream faulted
+            };
+            if let Ok(message) = serde_json::from_slice::<SyncMessage<T>>(&frame) {
+                if self.sync_channel.send(message).await.is_err() {
+                    break;
+                }
+            } else if let Ok(heartbeat) = serde_json::from_slice::<HeartbeatMessage>(&frame) {
+                self.apply_heartbeat(heartbeat).await;
+            }
+        }
+    }
+
+    /// Refresh a peer's liveness from its heartbeat, reviving a node we had
+    /// previously marked failed.
+    async fn apply_heartbeat(&self, heartbeat: HeartbeatMessage) {
+        let mut nodes = self.nodes.write().await;
+        if let Some(node) = nodes.get_mut(&heartbeat.node_id) {
+            node.last_heartbeat = Instant::now();
+            if node.status == NodeStatus::Failed {
+                node.status = NodeStatus::Active;
+            }
+        }
+    }
+
+    async fn handle_sync_messages(self, mut rx: mpsc::Receiver<SyncMessage<T>>) {
         while let Some(message) = rx.recv().await {
             match message {
-                SyncMessage::Replicate { key, value } => {
-                    self.replicate_value(key, value).await;
+                SyncMessage::Replicate { key, value, version } => {
+                    self.apply_replicate(key, value, version).await;
                 }
                 SyncMessage::Invalidate { key } => {
-                    self.invalidate_value(key).await;
+                    self.store.write().await.remove(&key);
                 }
                 SyncMessage::RecoveryRequest { node_id } => {
                     self.handle_recovery_request(node_id).await;
                 }
+                SyncMessage::SummaryRequest { node_id, summary } => {
+                    self.handle_summary(node_id, summary).await;
+                }
+                SyncMessage::PullKeys { keys } => {
+                    self.push_keys(keys).await;
+                }
+            }
+        }
+    }
+
+    async fn apply_replicate(&self, key: String, value: T, version: u64) {
+        let mut store = self.store.write().await;
+        // Last-writer-wins on version, so out-of-order frames can't regress.
+        match store.get(&key) {
+            Some((_, existing)) if *existing >= version => {}
+            _ => {
+                store.insert(key, (value, version));
             }
         }
     }
 
     async fn replicate_value(&self, key: String, value: T) {
-        let nodes = self.nodes.read().await;
-        for node in nodes.values() {
-            if let Ok(mut stream) = TcpStream::connect(format!("{}:{}", node.address, node.port)).await {
-                let message = SyncMessage::Replicate {
-                    key: key.clone(),
-                    value: value.clone(),
-                };
-                let _ = serde_json::to_writer(&mut stream, &message);
+        let version = {
+            let mut store = self.store.write().await;
+            let version = store.get(&key).map(|(_, v)| v + 1).unwrap_or(1);
+            store.insert(key.clone(), (value.clone(), version));
+            version
+        };
+        let message = SyncMessage::Replicate { key, value, version };
+        self.broadcast(&message).await;
+    }
+
+    async fn invalidate_value(&self, key: String) {
+        self.store.write().await.remove(&key);
+        self.broadcast::<T>(&SyncMessage::Invalidate { key }).await;
+    }
+
+    async fn broadcast(&self, message: &SyncMessage<T>) {
+        let payload = match serde_json::to_vec(message) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+        let nodes: Vec<CacheNode> = self.nodes.read().await.values().cloned().collect();
+        for node in nodes {
+            self.send_framed(&node, &payload).await;
+        }
+    }
+
+    /// Write a length-delimited frame over the pooled connection to `node`.
+    async fn send_framed(&self, node: &CacheNode, payload: &[u8]) {
+        let stream = {
+            let mut pool = self.pool.lock().await;
+            match pool.connect(node).await {
+                Ok(s) => s,
+                Err(_) => return,
             }
+        };
+        let mut guard = stream.lock().await;
+        if write_frame(&mut *guard, payload).await.is_err() {
+            // A broken pipe invalidates the cached connection.
+            self.pool.lock().await.drop_node(&node.id);
         }
     }
 
-    async fn monitor_nodes(&self) {
+    /// Periodic anti-entropy: every tick, hand each peer our version
+    /// summary so it can pull only the keys that differ, and mark dead
+    /// nodes whose heartbeat has lapsed.
+    async fn monitor_nodes(self) {
         let mut interval = tokio::time::interval(Duration::from_secs(10));
         loop {
             interval.tick().await;
-            let mut nodes = self.nodes.write().await;
+
+            let summary = self.version_summary().await;
+            let nodes: Vec<CacheNode> = self.nodes.read().await.values().cloned().collect();
+            for node in &nodes {
+                let message = SyncMessage::<T>::SummaryRequest {
+                    node_id: self.local_node.id.clone(),
+                    summary: summary.clone(),
+                };
+                if let Ok(payload) = serde_json::to_vec(&message) {
+                    self.send_framed(node, &payload).await;
+                }
+            }
+
             let now = Instant::now();
-            
+            let mut nodes = self.nodes.write().await;
             for node in nodes.values_mut() {
                 if now.duration_since(node.last_heartbeat) > Duration::from_secs(30) {
                     node.status = NodeStatus::Failed;
-                    self.recovery_manager.handle_node_failure(node.id.clone()).await;
+                    self.recovery_manager
+                        .lock()
+                        .await
+                        .handle_node_failure(node.id.clone())
+                        .await;
                 }
             }
         }
     }
-} 
\ No newline at end of file
+
+    async fn version_summary(&self) -> VersionSummary {
+        let store = self.store.read().await;
+        VersionSummary {
+            versions: store.iter().map(|(k, (_, v))| (k.clone(), *v)).collect(),
+        }
+    }
+
+    /// A peer sent its summary; pull back the keys where ours is stale.
+    async fn handle_summary(&self, node_id: String, peer_summary: VersionSummary) {
+        let local = self.version_summary().await;
+        let stale = peer_summary.diff(&local);
+
+        // Once our view matches the cluster, a Recovering/Syncing node has
+        // converged and recovery is complete.
+        if local.root() == peer_summary.root() {
+            self.recovery_manager
+                .lock()
+                .await
+                .update_recovery_status(&node_id, RecoveryStatus::Success);
+            return;
+        }
+
+        if let Some(node) = self.nodes.read().await.get(&node_id).cloned() {
+            let message = SyncMessage::<T>::PullKeys { keys: stale };
+            if let Ok(payload) = serde_json::to_vec(&message) {
+                self.send_framed(&node, &payload).await;
+            }
+        }
+    }
+
+    /// Push the requested keys' current values to every peer.
+    async fn push_keys(&self, keys: Vec<String>) {
+        let entries: Vec<(String, T, u64)> = {
+            let store = self.store.read().await;
+            keys.into_iter()
+                .filter_map(|k| store.get(&k).map(|(v, ver)| (k, v.clone(), *ver)))
+                .collect()
+        };
+        for (key, value, version) in entries {
+            self.broadcast(&SyncMessage::Replicate { key, value, version }).await;
+        }
+    }
+
+    async fn handle_recovery_request(&self, node_id: String) {
+        if let Some(node) = self.nodes.write().await.get_mut(&node_id) {
+            node.status = NodeStatus::Recovering;
+        }
+    }
+}
+
+/// Read a length-delimited frame: a big-endian `u32` length followed by
+/// that many bytes.
+pub async fn read_frame<R: AsyncReadExt + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let len = reader.read_u32().await? as usize;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+/// Write a length-delimited frame.
+pub async fn write_frame<W: AsyncWriteExt + Unpin>(
+    writer: &mut W,
+    payload: &[u8],
+) -> std::io::Result<()> {
+    writer.write_u32(payload.len() as u32).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -1,12 +1,14 @@
 use std::collections::VecDeque;
 use serde::{Serialize, Deserialize};
-use std::time::{Instant, Duration};
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheHistoryEntry {
-    timestamp: Instant,
+    /// Epoch milliseconds, so the timestamp is meaningful across process
+    /// restarts (an `Instant` only makes sense within a single process).
+    timestamp: u64,
     operation: CacheOperation,
     key: String,
     value_size: usize,
@@ -22,71 +24,167 @@ pub enum CacheOperation {
     Recovery,
 }
 
+/// Width of the on-disk key: an 8-byte big-endian sequence number prefixing
+/// every record, mirroring the fixed-width BigEndian keys embedded KV stores
+/// use for append-only logs.
+const KEY_LEN: usize = 8;
+/// Width of the big-endian payload length that follows each key.
+const LEN_LEN: usize = 4;
+
 pub struct CacheHistory {
     entries: VecDeque<CacheHistoryEntry>,
     max_entries: usize,
     log_file: String,
-    last_backup: Instant,
-    backup_interval: Duration,
+    /// Monotonically increasing sequence number used as the on-disk key for
+    /// the next appended record.
+    next_seq: u64,
+    /// Buffered append handle, opened lazily on the first write and kept open
+    /// for the process lifetime so the hot path never reopens the file.
+    writer: Option<BufWriter<File>>,
 }
 
 impl CacheHistory {
     pub fn new(max_entries: usize, log_file: &str) -> Self {
-        Self {
+        let mut history = Self {
             entries: VecDeque::with_capacity(max_entries),
             max_entries,
             log_file: log_file.to_string(),
-            last_backup: Instant::now(),
-            backup_interval: Duration::from_secs(300), // 5 minutes
-        }
+            next_seq: 0,
+            writer: None,
+        };
+        // Pick up where a prior process left off: replaying the existing log
+        // restores `next_seq` so appended records keep unique on-disk keys
+        // across restarts instead of colliding from zero. A malformed log is
+        // not fatal here — we simply start fresh.
+        let _ = history.recover();
+        history
     }
 
     pub fn record(&mut self, operation: CacheOperation, key: String, value_size: usize, success: bool) {
         let entry = CacheHistoryEntry {
-            timestamp: Instant::now(),
+            timestamp: now_millis(),
             operation,
             key,
             value_size,
             success,
         };
 
+        // Durably append the record before touching the in-memory ring, so a
+        // crash can only ever lose the entry we were mid-writing.
+        let _ = self.append(&entry);
+
         if self.entries.len() >= self.max_entries {
             self.entries.pop_front();
         }
         self.entries.push_back(entry);
-        self.maybe_backup();
     }
 
-    fn maybe_backup(&mut self) {
-        if self.last_backup.elapsed() >= self.backup_interval {
-            self.backup();
-            self.last_backup = Instant::now();
+    /// Append one length-prefixed record to the log: an 8-byte big-endian
+    /// sequence key, a 4-byte big-endian payload length, then the JSON body.
+    fn append(&mut self, entry: &CacheHistoryEntry) -> std::io::Result<()> {
+        let payload = serde_json::to_vec(entry)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if self.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.log_file)?;
+            self.writer = Some(BufWriter::new(file));
         }
+        let writer = self.writer.as_mut().expect("writer opened above");
+        writer.write_all(&self.next_seq.to_be_bytes())?;
+        writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+        writer.write_all(&payload)?;
+        // Flush each record so a crash loses at most the in-flight write; the
+        // buffered handle still spares us a reopen per call.
+        writer.flush()?;
+        self.next_seq += 1;
+        Ok(())
     }
 
-    fn backup(&self) {
-        let backup_data = serde_json::to_string(&self.entries).unwrap();
-        fs::write(&self.log_file, backup_data).expect("Failed to write backup");
+    pub fn recover(&mut self) -> Result<(), String> {
+        if !Path::new(&self.log_file).exists() {
+            return Ok(());
+        }
+
+        let mut data = Vec::new();
+        File::open(&self.log_file)
+            .and_then(|mut f| f.read_to_end(&mut data))
+            .map_err(|e| format!("Failed to read log: {}", e))?;
+
+        // Replay the log forward. A partial write (torn final record) is not a
+        // corruption: stop at the last fully readable record rather than
+        // failing the whole parse.
+        let mut entries = VecDeque::with_capacity(self.max_entries);
+        let mut offset = 0;
+        let mut last_seq = None;
+        while offset + KEY_LEN + LEN_LEN <= data.len() {
+            let seq = u64::from_be_bytes(data[offset..offset + KEY_LEN].try_into().unwrap());
+            let len_start = offset + KEY_LEN;
+            let len = u32::from_be_bytes(
+                data[len_start..len_start + LEN_LEN].try_into().unwrap(),
+            ) as usize;
+            let body_start = len_start + LEN_LEN;
+            if body_start + len > data.len() {
+                break; // truncated final entry
+            }
+            let entry: CacheHistoryEntry = match serde_json::from_slice(&data[body_start..body_start + len]) {
+                Ok(e) => e,
+                Err(_) => break, // unreadable final entry; keep what we have
+            };
+            if entries.len() >= self.max_entries {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+            last_seq = Some(seq);
+            offset = body_start + len;
+        }
+
+        // Cut off a torn tail so records appended from here on follow the
+        // last readable one instead of landing behind unreadable bytes.
+        if offset < data.len() {
+            OpenOptions::new()
+                .write(true)
+                .open(&self.log_file)
+                .and_then(|f| f.set_len(offset as u64))
+                .map_err(|e| format!("Failed to truncate log: {}", e))?;
+        }
+
+        self.entries = entries;
+        self.next_seq = last_seq.map_or(0, |s| s + 1);
+        Ok(())
     }
 
-    pub fn recover(&mut self) -> Result<(), String> {
-        if Path::new(&self.log_file).exists() {
-            let data = fs::read_to_string(&self.log_file)
-                .map_err(|e| format!("Failed to read backup: {}", e))?;
-            
-            let recovered: VecDeque<CacheHistoryEntry> = serde_json::from_str(&data)
-                .map_err(|e| format!("Failed to parse backup: {}", e))?;
-            
-            self.entries = recovered;
-            Ok(())
-        } else {
-            Ok(())
+    /// Fold the log back down to at most `max_entries` records by rewriting it
+    /// from the in-memory ring, re-keying sequence numbers from zero. This
+    /// reclaims the space of replaced/evicted entries that the append-only
+    /// path never removes.
+    pub fn compact(&mut self) -> Result<(), String> {
+        let tmp = format!("{}.compact", self.log_file);
+        let mut file = File::create(&tmp).map_err(|e| format!("Failed to create temp log: {}", e))?;
+        let mut seq: u64 = 0;
+        for entry in &self.entries {
+            let payload = serde_json::to_vec(entry)
+                .map_err(|e| format!("Failed to encode entry: {}", e))?;
+            let write = |file: &mut File| -> std::io::Result<()> {
+                file.write_all(&seq.to_be_bytes())?;
+                file.write_all(&(payload.len() as u32).to_be_bytes())?;
+                file.write_all(&payload)
+            };
+            write(&mut file).map_err(|e| format!("Failed to write compacted log: {}", e))?;
+            seq += 1;
         }
+        fs::rename(&tmp, &self.log_file).map_err(|e| format!("Failed to swap log: {}", e))?;
+        // The old handle points at the replaced inode; reopen lazily against
+        // the freshly compacted file on the next append.
+        self.writer = None;
+        self.next_seq = seq;
+        Ok(())
     }
 
     pub fn get_stats(&self) -> CacheHistoryStats {
         let mut stats = CacheHistoryStats::default();
-        
+
         for entry in &self.entries {
             match entry.operation {
                 CacheOperation::Insert => stats.inserts += 1,
@@ -101,11 +199,18 @@ impl CacheHistory {
                 stats.failed_operations += 1;
             }
         }
-        
+
         stats
     }
 }
 
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[derive(Debug, Default)]
 pub struct CacheHistoryStats {
     pub inserts: u64,
@@ -115,4 +220,4 @@ pub struct CacheHistoryStats {
     pub recoveries: u64,
     pub successful_operations: u64,
     pub failed_operations: u64,
-} 
\ No newline at end of file
+}
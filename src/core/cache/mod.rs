@@ -1,30 +1,88 @@
+pub mod arc;
+pub mod codec;
+pub mod distributed;
+pub mod history;
+pub mod recovery;
+
 use std::collections::{HashMap, BTreeMap};
+use std::marker::PhantomData;
 use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use rayon::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use self::arc::{ArcPolicy, EvictionPolicy};
+use self::codec::{CacheCodec, Compression, ZlibCodec};
+use self::history::{CacheHistory, CacheHistoryStats, CacheOperation};
 
 pub struct EnhancedCache<T> {
-    entries: Arc<RwLock<HashMap<String, CacheEntry<T>>>>,
+    // Entries are stored as compressed byte blobs, decoded lazily on read.
+    entries: Arc<RwLock<HashMap<String, CacheEntry<Vec<u8>>>>>,
     access_patterns: Arc<RwLock<BTreeMap<String, AccessPattern>>>,
     history: Arc<RwLock<CacheHistory>>,
     max_size: usize,
     ttl: Duration,
     hit_count: Arc<RwLock<u64>>,
     miss_count: Arc<RwLock<u64>>,
-    compression_enabled: bool,
+    codec: Arc<ZlibCodec>,
+    compression: Compression,
     predictive_caching: bool,
+    policy: EvictionPolicy,
+    arc: Arc<RwLock<ArcPolicy>>,
+    // Running total of compressed bytes currently stored, for stats.
+    bytes_stored: Arc<AtomicU64>,
+    // Running total of the pre-compression serialized size of entries
+    // currently resident. Mirrors `bytes_stored` over the same population
+    // (not an all-time accumulator) so `compression_ratio` stays meaningful
+    // across evictions and overwrites.
+    bytes_raw: Arc<AtomicU64>,
+    // Per-key raw size, so `bytes_raw` can be decremented by the right
+    // amount when a key is evicted or overwritten.
+    raw_sizes: Arc<RwLock<HashMap<String, usize>>>,
+    _marker: PhantomData<T>,
 }
 
+/// Smoothing factor for the EWMA interval estimator. A higher alpha reacts
+/// faster to recent accesses; 0.3 keeps a little memory of past intervals.
+const EWMA_ALPHA: f64 = 0.3;
+
+/// Maximum relative standard deviation (sqrt(variance) / mean) for which a
+/// predicted next access is trusted. Above this the key is too bursty to
+/// predict, so `predicted_next_access` is left `None`.
+const PREDICTION_REL_STDDEV: f64 = 0.5;
+
 #[derive(Debug)]
 struct AccessPattern {
     last_access: Instant,
     access_count: u64,
-    access_times: Vec<Instant>,
+    /// Exponentially weighted moving average of the inter-access interval,
+    /// in seconds. `None` until the second access establishes one interval.
+    ewma_interval: Option<f64>,
+    /// EWMA of the squared deviation from `ewma_interval`, i.e. a running
+    /// variance estimate for the interval.
+    ewma_variance: f64,
     predicted_next_access: Option<Instant>,
 }
 
-impl<T: Clone + Send + Sync> EnhancedCache<T> {
+impl<T: Clone + Send + Sync + Serialize + DeserializeOwned> EnhancedCache<T> {
     pub fn new(max_size: usize, ttl_seconds: u64) -> Self {
+        Self::with_options(max_size, ttl_seconds, Compression::Fast, EvictionPolicy::Predictive)
+    }
+
+    /// Construct a cache with an explicit compression level.
+    pub fn with_compression(max_size: usize, ttl_seconds: u64, compression: Compression) -> Self {
+        Self::with_options(max_size, ttl_seconds, compression, EvictionPolicy::Predictive)
+    }
+
+    /// Construct a cache selecting both compression and eviction policy.
+    pub fn with_options(
+        max_size: usize,
+        ttl_seconds: u64,
+        compression: Compression,
+        policy: EvictionPolicy,
+    ) -> Self {
         Self {
             entries: Arc::new(RwLock::new(HashMap::with_capacity(max_size))),
             access_patterns: Arc::new(RwLock::new(BTreeMap::new())),
@@ -33,8 +91,15 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
             ttl: Duration::from_secs(ttl_seconds),
             hit_count: Arc::new(RwLock::new(0)),
             miss_count: Arc::new(RwLock::new(0)),
-            compression_enabled: true,
+            codec: Arc::new(ZlibCodec::new(compression)),
+            compression,
             predictive_caching: true,
+            policy,
+            arc: Arc::new(RwLock::new(ArcPolicy::new(max_size))),
+            bytes_stored: Arc::new(AtomicU64::new(0)),
+            bytes_raw: Arc::new(AtomicU64::new(0)),
+            raw_sizes: Arc::new(RwLock::new(HashMap::new())),
+            _marker: PhantomData,
         }
     }
 
@@ -55,6 +120,9 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
 
         // Regular cache lookup
         if let Some(value) = self.get_from_cache(key) {
+            if self.policy == EvictionPolicy::Arc {
+                self.arc.write().unwrap().touch(key);
+            }
             self.record_access(key, true);
             return Some(value);
         }
@@ -67,7 +135,9 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
         let entries = self.entries.read().unwrap();
         if let Some(entry) = entries.get(key) {
             if entry.created_at.elapsed() <= self.ttl {
-                Some(entry.value.clone())
+                // Decompress lazily: only the bytes actually read pay the
+                // decode cost.
+                self.codec.decode(&entry.value).ok()
             } else {
                 None
             }
@@ -78,30 +148,53 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
 
     fn record_access(&self, key: &str, success: bool) {
         let mut patterns = self.access_patterns.write().unwrap();
+        let now = Instant::now();
         let pattern = patterns.entry(key.to_string()).or_insert_with(|| AccessPattern {
-            last_access: Instant::now(),
+            last_access: now,
             access_count: 0,
-            access_times: Vec::new(),
+            ewma_interval: None,
+            ewma_variance: 0.0,
             predicted_next_access: None,
         });
 
-        pattern.last_access = Instant::now();
+        // Fold the interval since the previous access into the running EWMA
+        // statistics before advancing `last_access`. This keeps per-key state
+        // to two scalars instead of an unbounded history of timestamps.
+        let interval = now.duration_since(pattern.last_access).as_secs_f64();
+        pattern.last_access = now;
         pattern.access_count += 1;
-        pattern.access_times.push(Instant::now());
-
-        // Update prediction
-        if pattern.access_times.len() >= 3 {
-            let times: Vec<f64> = pattern.access_times
-                .windows(2)
-                .map(|w| w[1].duration_since(w[0]).as_secs_f64())
-                .collect();
-            
-            let avg_interval = times.iter().sum::<f64>() / times.len() as f64;
-            pattern.predicted_next_access = Some(
-                pattern.last_access + Duration::from_secs_f64(avg_interval)
-            );
+
+        if pattern.access_count >= 2 {
+            match pattern.ewma_interval {
+                None => {
+                    pattern.ewma_interval = Some(interval);
+                    pattern.ewma_variance = 0.0;
+                }
+                Some(prev) => {
+                    let deviation = interval - prev;
+                    pattern.ewma_interval =
+                        Some(EWMA_ALPHA * interval + (1.0 - EWMA_ALPHA) * prev);
+                    pattern.ewma_variance = EWMA_ALPHA * deviation * deviation
+                        + (1.0 - EWMA_ALPHA) * pattern.ewma_variance;
+                }
+            }
         }
 
+        // Only predict once the estimate is stable: a relative standard
+        // deviation below the threshold means the key is accessed on a
+        // regular-enough cadence to prefetch without polluting eviction.
+        pattern.predicted_next_access = match pattern.ewma_interval {
+            Some(ewma) if ewma > 0.0 => {
+                let rel_stddev = pattern.ewma_variance.sqrt() / ewma;
+                if rel_stddev < PREDICTION_REL_STDDEV {
+                    Some(pattern.last_access + Duration::from_secs_f64(ewma))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
         // Record in history
         let history = self.history.write().unwrap();
         history.record(
@@ -120,41 +213,66 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
     }
 
     pub fn insert(&self, key: String, value: T) {
-        let mut entries = self.entries.write().unwrap();
-        
-        // Check if we need to evict
-        if entries.len() >= self.max_size {
-            self.evict_entries();
+        match self.policy {
+            EvictionPolicy::Arc => {
+                // ARC decides admission and which resident keys to drop.
+                let evicted = self.arc.write().unwrap().reference(&key);
+                let mut entries = self.entries.write().unwrap();
+                let mut raw_sizes = self.raw_sizes.write().unwrap();
+                for k in evicted {
+                    if let Some(entry) = entries.remove(&k) {
+                        self.bytes_stored.fetch_sub(entry.value.len() as u64, Ordering::Relaxed);
+                    }
+                    if let Some(raw_size) = raw_sizes.remove(&k) {
+                        self.bytes_raw.fetch_sub(raw_size as u64, Ordering::Relaxed);
+                    }
+                }
+            }
+            EvictionPolicy::Predictive => {
+                let entries = self.entries.read().unwrap();
+                if entries.len() >= self.max_size {
+                    drop(entries);
+                    self.evict_entries();
+                }
+            }
         }
 
-        // Compress value if needed
-        let value = if self.compression_enabled {
-            self.compress_value(value)
-        } else {
-            value
+        // Serialize + compress into the stored blob.
+        let raw_size = serde_json::to_vec(&value).map(|v| v.len()).unwrap_or(0);
+        let blob = match self.codec.encode(&value) {
+            Ok(bytes) => bytes,
+            Err(_) => return,
         };
+        let stored_size = blob.len();
 
+        // Drop the previous blob's byte contribution, if any.
+        let mut entries = self.entries.write().unwrap();
+        let mut raw_sizes = self.raw_sizes.write().unwrap();
+        if let Some(old) = entries.get(&key) {
+            self.bytes_stored.fetch_sub(old.value.len() as u64, Ordering::Relaxed);
+        }
+        if let Some(old_raw_size) = raw_sizes.insert(key.clone(), raw_size) {
+            self.bytes_raw.fetch_sub(old_raw_size as u64, Ordering::Relaxed);
+        }
         entries.insert(key.clone(), CacheEntry {
-            value,
+            value: blob,
             created_at: Instant::now(),
             last_accessed: Instant::now(),
             access_count: 0,
         });
+        self.bytes_stored.fetch_add(stored_size as u64, Ordering::Relaxed);
+        self.bytes_raw.fetch_add(raw_size as u64, Ordering::Relaxed);
 
-        // Record in history
+        // Record the real compressed size, not the pointer width.
         let history = self.history.write().unwrap();
-        history.record(
-            CacheOperation::Insert,
-            key,
-            std::mem::size_of_val(&value),
-            true,
-        );
+        history.record(CacheOperation::Insert, key, stored_size, true);
     }
 
     fn evict_entries(&self) {
         let mut entries = self.entries.write().unwrap();
         let mut patterns = self.access_patterns.write().unwrap();
-        
+        let mut raw_sizes = self.raw_sizes.write().unwrap();
+
         // Sort entries by last access and predicted next access
         let mut to_evict: Vec<String> = entries
             .iter()
@@ -173,16 +291,16 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
 
         // Remove entries
         for key in to_evict {
-            entries.remove(&key);
+            if let Some(entry) = entries.remove(&key) {
+                self.bytes_stored.fetch_sub(entry.value.len() as u64, Ordering::Relaxed);
+            }
+            if let Some(raw_size) = raw_sizes.remove(&key) {
+                self.bytes_raw.fetch_sub(raw_size as u64, Ordering::Relaxed);
+            }
             patterns.remove(&key);
         }
     }
 
-    fn compress_value(&self, value: T) -> T {
-        // Implement compression logic here
-        value
-    }
-
     pub fn recover(&self) -> Result<(), String> {
         self.history.write().unwrap().recover()
     }
@@ -206,10 +324,36 @@ impl<T: Clone + Send + Sync> EnhancedCache<T> {
             predictive_hits: patterns.values()
                 .filter(|p| p.predicted_next_access.is_some())
                 .count(),
+            bytes_stored: self.bytes_stored.load(Ordering::Relaxed),
+            compression_ratio: {
+                let raw = self.bytes_raw.load(Ordering::Relaxed);
+                let stored = self.bytes_stored.load(Ordering::Relaxed);
+                if stored > 0 { raw as f64 / stored as f64 } else { 1.0 }
+            },
+            arc_stats: {
+                let arc = self.arc.read().unwrap();
+                ArcStats {
+                    p: arc.target_p(),
+                    t1: arc.t1_len(),
+                    t2: arc.t2_len(),
+                    b1: arc.b1_len(),
+                    b2: arc.b2_len(),
+                }
+            },
         }
     }
 }
 
+/// Snapshot of the ARC policy's internal list sizes and target `p`.
+#[derive(Debug, Default)]
+pub struct ArcStats {
+    pub p: usize,
+    pub t1: usize,
+    pub t2: usize,
+    pub b1: usize,
+    pub b2: usize,
+}
+
 #[derive(Debug)]
 pub struct EnhancedCacheStats {
     pub size: usize,
@@ -218,4 +362,11 @@ pub struct EnhancedCacheStats {
     pub hit_ratio: f64,
     pub history_stats: CacheHistoryStats,
     pub predictive_hits: usize,
+    /// Total compressed bytes currently resident.
+    pub bytes_stored: u64,
+    /// Raw-to-compressed size ratio (higher means better savings).
+    pub compression_ratio: f64,
+    /// Current ARC list sizes and target `p` (meaningful when the ARC
+    /// policy is selected).
+    pub arc_stats: ArcStats,
 } 
\ No newline at end of file
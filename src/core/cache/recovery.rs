@@ -78,8 +78,13 @@ impl RecoveryManager {
         true
     }
 
-    fn update_recovery_status(&mut self, node_id: &str, status: RecoveryStatus) {
-        if let Some(attempt) = self.recovery_history.last_mut() {
+    pub fn update_recovery_status(&mut self, node_id: &str, status: RecoveryStatus) {
+        if let Some(attempt) = self
+            .recovery_history
+            .iter_mut()
+            .rev()
+            .find(|attempt| attempt.node_id == node_id)
+        {
             attempt.status = status;
         }
     }
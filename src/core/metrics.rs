@@ -6,6 +6,15 @@ pub struct PerformanceMetrics {
 }
 
 impl PerformanceMetrics {
+    pub fn new() -> Self {
+        Self {
+            cache_hits: 0,
+            cache_misses: 0,
+            parse_time: Vec::new(),
+            eval_time: Vec::new(),
+        }
+    }
+
     pub fn record_cache_hit(&mut self) {
         self.cache_hits += 1;
     }
@@ -0,0 +1,196 @@
+use wasm_bindgen::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use rayon::prelude::*;
+
+use crate::utils::signer::{address_from_public, hex_decode, hex_encode, keccak256};
+
+/// Deterministic brain keys and vanity address search, layered on the
+/// signing subsystem.
+///
+/// A brain key is derived from a passphrase by hashing it repeatedly with
+/// keccak; the vanity search hunts for an address whose leading hex
+/// nibbles match a requested prefix.
+#[wasm_bindgen]
+pub struct BrainPrefix {
+    secp: Secp256k1<secp256k1::All>,
+    /// Upper bound on secrets tried per prefix search before giving up,
+    /// so a long prefix can't spin forever.
+    attempt_cap: u64,
+}
+
+#[wasm_bindgen]
+impl BrainPrefix {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            secp: Secp256k1::new(),
+            attempt_cap: 10_000_000,
+        }
+    }
+
+    /// Override the per-search attempt cap.
+    pub fn set_attempt_cap(&mut self, cap: u64) {
+        self.attempt_cap = cap;
+    }
+
+    /// Derive a keypair deterministically from `passphrase`, returning
+    /// `secret_hex:public_hex:address_hex`. The phrase is hashed
+    /// `rounds` times with keccak to stretch it into the 32-byte secret.
+    pub fn from_passphrase(&self, passphrase: &str, rounds: u32) -> Result<String, JsValue> {
+        let secret = derive_secret(passphrase, rounds.max(1));
+        let key = SecretKey::from_slice(&secret).map_err(err)?;
+        let public = PublicKey::from_secret_key(&self.secp, &key);
+        Ok(format!(
+            "{}:{}:{}",
+            hex_encode(&secret),
+            hex_encode(&public.serialize_uncompressed()),
+            hex_encode(&address_from_public(&public)),
+        ))
+    }
+
+    /// Search for an address whose leading hex nibbles equal `prefix`,
+    /// returning `secret_hex:address_hex:attempts` for the first match.
+    ///
+    /// Work is fanned out across rayon's worker pool; a shared atomic flag
+    /// stops every worker as soon as one succeeds, and the attempt cap
+    /// bounds total effort.
+    pub fn find_prefix(&self, prefix: &str) -> Result<String, JsValue> {
+        let prefix = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+        if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(JsValue::from_str("prefix must be non-empty hex"));
+        }
+
+        let found = Arc::new(AtomicBool::new(false));
+        // Count secrets actually tried, not the rayon range index: under a
+        // parallel search the index a worker happens to hit on says nothing
+        // about how many candidates the pool consumed overall.
+        let attempts = Arc::new(AtomicU64::new(0));
+        let cap = self.attempt_cap;
+        let secp = &self.secp;
+
+        let hit = (0..cap)
+            .into_par_iter()
+            .find_map_any(|_| {
+                if found.load(Ordering::Relaxed) {
+                    return None;
+                }
+                let mut seed = [0u8; 32];
+                if getrandom::getrandom(&mut seed).is_err() {
+                    return None;
+                }
+                attempts.fetch_add(1, Ordering::Relaxed);
+                let key = SecretKey::from_slice(&seed).ok()?;
+                let public = PublicKey::from_secret_key(secp, &key);
+                let address = address_from_public(&public);
+                if hex_encode(&address).starts_with(&prefix) {
+                    found.store(true, Ordering::Relaxed);
+                    Some((hex_encode(&seed), hex_encode(&address)))
+                } else {
+                    None
+                }
+            });
+
+        match hit {
+            Some((secret, address)) => {
+                let tried = attempts.load(Ordering::Relaxed);
+                Ok(format!("{}:{}:{}", secret, address, tried))
+            }
+            None => Err(JsValue::from_str("prefix not found within attempt cap")),
+        }
+    }
+
+    /// Recover a passphrase that produces `target_address` by enumerating
+    /// candidates within `max_edits` single-character edits (substitution,
+    /// insertion, deletion) of `known_phrase`. `rounds` must match the key
+    /// stretching used by [`from_passphrase`](Self::from_passphrase) when the
+    /// target was generated, otherwise the derived address never matches.
+    /// Returns the first matching phrase, or an error if none is found within
+    /// the edit budget.
+    pub fn brain_recover(
+        &self,
+        known_phrase: &str,
+        target_address: &str,
+        max_edits: u32,
+        rounds: u32,
+    ) -> Result<String, JsValue> {
+        let target = hex_decode(target_address)?;
+        if target.len() != 20 {
+            return Err(JsValue::from_str("target address must be 20 bytes"));
+        }
+
+        let mut frontier = vec![known_phrase.to_string()];
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..=max_edits {
+            let mut next = Vec::new();
+            for phrase in frontier.drain(..) {
+                if !seen.insert(phrase.clone()) {
+                    continue;
+                }
+                if self.address_matches(&phrase, &target, rounds)? {
+                    return Ok(phrase);
+                }
+                next.extend(edit_neighbours(&phrase));
+            }
+            frontier = next;
+        }
+        Err(JsValue::from_str("no phrase found within edit distance"))
+    }
+}
+
+impl BrainPrefix {
+    fn address_matches(&self, phrase: &str, target: &[u8], rounds: u32) -> Result<bool, JsValue> {
+        // Stretch with the same round count `from_passphrase` uses, so
+        // recovery works for keys generated with `rounds > 1`.
+        let secret = derive_secret(phrase, rounds.max(1));
+        let key = SecretKey::from_slice(&secret).map_err(err)?;
+        let public = PublicKey::from_secret_key(&self.secp, &key);
+        Ok(address_from_public(&public) == target)
+    }
+}
+
+/// Hash `passphrase` `rounds` times with keccak to produce a 32-byte secret.
+fn derive_secret(passphrase: &str, rounds: u32) -> [u8; 32] {
+    let mut digest = keccak256(passphrase.as_bytes());
+    for _ in 1..rounds {
+        digest = keccak256(&digest);
+    }
+    digest
+}
+
+/// All single-edit neighbours of `phrase`: substitutions, insertions and
+/// deletions over lowercase ASCII letters, digits and space.
+fn edit_neighbours(phrase: &str) -> Vec<String> {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789 ";
+    let chars: Vec<char> = phrase.chars().collect();
+    let mut out = Vec::new();
+
+    // Substitutions
+    for i in 0..chars.len() {
+        for &c in ALPHABET {
+            let mut candidate = chars.clone();
+            candidate[i] = c as char;
+            out.push(candidate.into_iter().collect());
+        }
+    }
+    // Insertions
+    for i in 0..=chars.len() {
+        for &c in ALPHABET {
+            let mut candidate = chars.clone();
+            candidate.insert(i, c as char);
+            out.push(candidate.into_iter().collect());
+        }
+    }
+    // Deletions
+    for i in 0..chars.len() {
+        let mut candidate = chars.clone();
+        candidate.remove(i);
+        out.push(candidate.into_iter().collect());
+    }
+    out
+}
+
+fn err<E: std::fmt::Display>(e: E) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
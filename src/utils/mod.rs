@@ -0,0 +1,3 @@
+pub mod brain_prefix;
+pub mod hex_converter;
+pub mod signer;
@@ -0,0 +1,155 @@
+use wasm_bindgen::prelude::*;
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Message, PublicKey, Secp256k1, SecretKey,
+};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Message authentication for the hex blobs produced by
+/// [`crate::utils::hex_converter::LumaHexConverter`].
+///
+/// Everything crossing the boundary stays hex-encoded to match the
+/// converter's format: secret keys (32 bytes), uncompressed public keys
+/// (65 bytes), 20-byte addresses, messages, and 65-byte recoverable
+/// signatures (`r ‖ s ‖ v`). All failure modes — bad length, invalid
+/// point, non-hex input — come back as `Err(JsValue)`.
+#[wasm_bindgen]
+pub struct LumaSigner {
+    secp: Secp256k1<secp256k1::All>,
+}
+
+#[wasm_bindgen]
+impl LumaSigner {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            secp: Secp256k1::new(),
+        }
+    }
+
+    /// Generate a fresh keypair, returning `secret_hex:public_hex:address_hex`.
+    pub fn generate(&self) -> Result<String, JsValue> {
+        let mut seed = [0u8; 32];
+        getrandom::getrandom(&mut seed).map_err(err)?;
+        let secret = SecretKey::from_slice(&seed).map_err(err)?;
+        let public = PublicKey::from_secret_key(&self.secp, &secret);
+        Ok(format!(
+            "{}:{}:{}",
+            hex_encode(&secret.secret_bytes()),
+            hex_encode(&public.serialize_uncompressed()),
+            hex_encode(&address_from_public(&public)),
+        ))
+    }
+
+    /// Sign `message_hex` with `secret_hex`, returning a 65-byte
+    /// recoverable signature as hex.
+    pub fn sign(&self, secret_hex: &str, message_hex: &str) -> Result<String, JsValue> {
+        let secret = SecretKey::from_slice(&hex_decode(secret_hex)?).map_err(err)?;
+        let digest = keccak256(&hex_decode(message_hex)?);
+        let msg = Message::from_digest_slice(&digest).map_err(err)?;
+        let sig = self.secp.sign_ecdsa_recoverable(&msg, &secret);
+        Ok(hex_encode(&encode_recoverable(&sig)))
+    }
+
+    /// Verify that `signature_hex` over `message_hex` recovers to the
+    /// uncompressed public key `public_hex`.
+    pub fn verify_public(
+        &self,
+        public_hex: &str,
+        signature_hex: &str,
+        message_hex: &str,
+    ) -> Result<bool, JsValue> {
+        let expected = PublicKey::from_slice(&hex_decode(public_hex)?).map_err(err)?;
+        let recovered = self.recover(signature_hex, message_hex)?;
+        Ok(recovered == expected)
+    }
+
+    /// Verify that `signature_hex` over `message_hex` recovers to a public
+    /// key whose 20-byte address equals `address_hex`.
+    pub fn verify_address(
+        &self,
+        address_hex: &str,
+        signature_hex: &str,
+        message_hex: &str,
+    ) -> Result<bool, JsValue> {
+        let expected = hex_decode(address_hex)?;
+        let recovered = self.recover(signature_hex, message_hex)?;
+        Ok(address_from_public(&recovered) == expected.as_slice())
+    }
+
+    /// Recover the uncompressed public key that produced `signature_hex`
+    /// over `message_hex`, returned as hex.
+    pub fn recover_public(&self, signature_hex: &str, message_hex: &str) -> Result<String, JsValue> {
+        let public = self.recover(signature_hex, message_hex)?;
+        Ok(hex_encode(&public.serialize_uncompressed()))
+    }
+}
+
+impl LumaSigner {
+    fn recover(&self, signature_hex: &str, message_hex: &str) -> Result<PublicKey, JsValue> {
+        let sig = decode_recoverable(&hex_decode(signature_hex)?)?;
+        let digest = keccak256(&hex_decode(message_hex)?);
+        let msg = Message::from_digest_slice(&digest).map_err(err)?;
+        self.secp.recover_ecdsa(&msg, &sig).map_err(err)
+    }
+}
+
+/// keccak-256 of `bytes`.
+pub(crate) fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut out = [0u8; 32];
+    hasher.update(bytes);
+    hasher.finalize(&mut out);
+    out
+}
+
+/// Derive the 20-byte address: the last 20 bytes of the keccak hash of the
+/// uncompressed public key (with the `0x04` prefix byte dropped).
+pub(crate) fn address_from_public(public: &PublicKey) -> [u8; 20] {
+    let uncompressed = public.serialize_uncompressed();
+    let hash = keccak256(&uncompressed[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+fn encode_recoverable(sig: &RecoverableSignature) -> [u8; 65] {
+    let (recovery_id, compact) = sig.serialize_compact();
+    let mut out = [0u8; 65];
+    out[..64].copy_from_slice(&compact);
+    out[64] = recovery_id.to_i32() as u8;
+    out
+}
+
+fn decode_recoverable(bytes: &[u8]) -> Result<RecoverableSignature, JsValue> {
+    if bytes.len() != 65 {
+        return Err(JsValue::from_str("signature must be 65 bytes (r‖s‖v)"));
+    }
+    let recovery_id = RecoveryId::from_i32(bytes[64] as i32).map_err(err)?;
+    RecoverableSignature::from_compact(&bytes[..64], recovery_id).map_err(err)
+}
+
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn hex_decode(hex: &str) -> Result<Vec<u8>, JsValue> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() {
+        return Err(JsValue::from_str("invalid hex digit"));
+    }
+    if hex.len() % 2 != 0 {
+        return Err(JsValue::from_str("hex input has odd length"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| JsValue::from_str("invalid hex digit"))
+        })
+        .collect()
+}
+
+fn err<E: std::fmt::Display>(e: E) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
@@ -0,0 +1,126 @@
+//! Deterministic fuel metering.
+//!
+//! Untrusted hex payloads can contain unbounded loops, so before a module
+//! runs we compute a cost plan for it. Each function body is split into
+//! *metered blocks* whose boundaries are the function entry and every
+//! position immediately following a control-flow instruction; for each
+//! block we statically sum a per-opcode cost, keyed by the block's start
+//! offset. The runtime then charges a block's cost exactly when its `pc`
+//! reaches that offset — whether by falling through, branching, or taking
+//! a loop back-edge — so only the blocks a run actually takes are charged,
+//! and a runaway loop still drains fuel because every iteration re-reaches
+//! the same offset.
+//!
+//! The plan does not rewrite the opcode stream, so execution stays
+//! stack-balanced and relative branch depths are preserved.
+
+use std::collections::HashMap;
+
+use crate::wasm::runtime::{self, Module, Trap};
+
+/// Per-opcode static cost. Simple stack/arithmetic ops cost 1; memory and
+/// call ops are heavier because they touch linear memory or a new frame.
+fn opcode_cost(op: u8) -> u64 {
+    match op {
+        // memory load/store
+        0x28..=0x3e => 4,
+        // call / call_indirect
+        0x10 | 0x11 => 8,
+        // everything else is a simple op
+        _ => 1,
+    }
+}
+
+/// Identify the opcodes that terminate a metered block. A block ends
+/// immediately after any of these so the next block's charge is evaluated
+/// before its body runs.
+fn is_block_boundary(op: u8) -> bool {
+    matches!(
+        op,
+        0x02 | 0x03 | 0x04 | 0x05 | 0x0c | 0x0d | 0x0e | 0x10 | 0x0f | 0x0b
+    )
+}
+
+/// A static cost map consulted by the runtime during execution.
+#[derive(Debug, Default, Clone)]
+pub struct FuelPlan {
+    /// Per-function map from a metered block's start offset to its static
+    /// cost, charged when the runtime's `pc` reaches that offset.
+    pub block_costs: Vec<HashMap<usize, u64>>,
+}
+
+/// Walk every function body and compute its metered-block costs. This does
+/// not mutate the module bytes — the runtime consults the plan and charges
+/// the fuel counter as `pc` reaches each block's start offset, which keeps
+/// execution stack-balanced without rewriting the opcode stream.
+pub fn plan(module: &Module) -> FuelPlan {
+    let mut block_costs = Vec::with_capacity(module.functions.len());
+    for func in &module.functions {
+        block_costs.push(segment_costs(&func.body));
+    }
+    FuelPlan { block_costs }
+}
+
+/// Partition `body` into straight-line segments at every block boundary —
+/// the function entry and the position right after each control-flow
+/// instruction — and map each segment's start offset to its static cost.
+///
+/// Because a block/loop/if header's immediate, an `else`, a branch, a call
+/// and an `end` are all boundaries, this single linear pass over the raw
+/// instruction stream produces exactly the offsets the runtime can land on
+/// via fallthrough, a branch, or a loop back-edge — so charging by segment
+/// start charges a block only when control actually reaches it, once per
+/// reach.
+fn segment_costs(body: &[u8]) -> HashMap<usize, u64> {
+    let mut costs = HashMap::new();
+    let mut segment_start = 0usize;
+    let mut cost = 0u64;
+    let mut pc = 0usize;
+    while pc < body.len() {
+        let op = match runtime::decode_instr(body, &mut pc) {
+            Ok(op) => op,
+            Err(_) => break,
+        };
+        cost = cost.saturating_add(opcode_cost(op));
+        if is_block_boundary(op) {
+            costs.insert(segment_start, cost);
+            segment_start = pc;
+            cost = 0;
+        }
+    }
+    if cost > 0 {
+        costs.insert(segment_start, cost);
+    }
+    costs
+}
+
+/// A mutable fuel counter seeded with a limit and drained as blocks run.
+#[derive(Debug)]
+pub struct Fuel {
+    remaining: i64,
+    consumed: u64,
+}
+
+impl Fuel {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            remaining: limit as i64,
+            consumed: 0,
+        }
+    }
+
+    /// Charge `amount` fuel, trapping if the counter would go negative.
+    pub fn charge(&mut self, amount: u64) -> Result<(), Trap> {
+        self.remaining -= amount as i64;
+        self.consumed = self.consumed.saturating_add(amount);
+        if self.remaining < 0 {
+            Err(Trap::OutOfFuel)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+}
@@ -1,12 +1,29 @@
 use wasm_bindgen::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+use crate::wasm::fuel::{self, Fuel};
+use crate::wasm::runtime::{self, ModuleInstance, Trap, Value};
+
+/// A host hook shared across every instance a [`WasmInterpreter`] creates.
+///
+/// Unlike [`runtime::HostFn`] (owned by a single [`ModuleInstance`]), this is
+/// `Arc`-backed so repeated `interpret*` calls can clone the registry into
+/// each fresh instance instead of consuming it.
+type SharedHostFn = Arc<dyn Fn(&[Value]) -> Result<Vec<Value>, Trap> + Send + Sync>;
 
 #[wasm_bindgen]
 pub struct WasmInterpreter {
     operations: Arc<AtomicUsize>,
     last_operation: String,
     monitor: OperationMonitor,
+    entry_point: String,
+    host_functions: Arc<Mutex<Vec<(String, SharedHostFn)>>>,
+    last_fuel_consumed: u64,
+    #[cfg(feature = "jit")]
+    jit_cache: crate::wasm::jit::JitCache,
+    #[cfg(feature = "jit")]
+    metrics: crate::core::metrics::PerformanceMetrics,
 }
 
 #[wasm_bindgen]
@@ -18,24 +35,43 @@ impl WasmInterpreter {
             operations: Arc::new(AtomicUsize::new(0)),
             last_operation: String::new(),
             monitor: OperationMonitor::new(),
+            entry_point: "main".to_string(),
+            host_functions: Arc::new(Mutex::new(Vec::new())),
+            last_fuel_consumed: 0,
+            #[cfg(feature = "jit")]
+            jit_cache: crate::wasm::jit::JitCache::new(128, 3600),
+            #[cfg(feature = "jit")]
+            metrics: crate::core::metrics::PerformanceMetrics::new(),
         }
     }
 
+    /// Set the exported function invoked by [`interpret`]; defaults to `main`.
+    pub fn set_entry_point(&mut self, name: &str) {
+        self.entry_point = name.to_string();
+    }
+
+    /// Decode `hex` into a WebAssembly module, instantiate it with the
+    /// registered host environment and invoke the configured entry point.
+    ///
+    /// Returns the entry point's first result value; traps (unreachable,
+    /// out-of-bounds memory, type mismatch, …) surface as `Err(JsValue)`
+    /// instead of panicking.
     pub fn interpret(&mut self, hex: &str) -> Result<JsValue, JsValue> {
         let start = std::time::Instant::now();
-        
+
         // Log operation start
         self.monitor.log_operation_start("interpret");
-        
+
         // Increment operation counter
         self.operations.fetch_add(1, Ordering::SeqCst);
-        
-        // Perform interpretation
-        let result = match self.process_hex(hex) {
-            Ok(val) => {
+        self.last_operation = "interpret".to_string();
+
+        // Parse and execute the module.
+        let result = match self.run(hex) {
+            Ok(values) => {
                 self.monitor.log_success();
-                Ok(JsValue::from_str(&val))
-            },
+                Ok(encode_results(&values))
+            }
             Err(e) => {
                 self.monitor.log_error(&e.to_string());
                 Err(JsValue::from_str(&e.to_string()))
@@ -44,16 +80,133 @@ impl WasmInterpreter {
 
         // Log performance
         self.monitor.log_performance(start.elapsed());
-        
+
+        result
+    }
+
+    /// Like [`interpret`], but instruments the module with fuel metering
+    /// before running so a runaway loop traps once `limit` fuel is spent
+    /// rather than hanging. The amount actually consumed is recorded and
+    /// surfaced through [`get_stats`].
+    pub fn interpret_with_fuel(&mut self, hex: &str, limit: u64) -> Result<JsValue, JsValue> {
+        let start = std::time::Instant::now();
+        self.monitor.log_operation_start("interpret_with_fuel");
+        self.operations.fetch_add(1, Ordering::SeqCst);
+        self.last_operation = "interpret_with_fuel".to_string();
+
+        let result = match self.run_metered(hex, limit) {
+            Ok((values, consumed)) => {
+                self.last_fuel_consumed = consumed;
+                self.monitor.log_success();
+                Ok(encode_results(&values))
+            }
+            Err(e) => {
+                self.monitor.log_error(&e.to_string());
+                Err(JsValue::from_str(&e.to_string()))
+            }
+        };
+
+        self.monitor.log_performance(start.elapsed());
         result
     }
 
     pub fn get_stats(&self) -> String {
-        format!(
-            "Operations: {}\nLast: {}\nSuccess Rate: {}%",
+        let mut stats = format!(
+            "Operations: {}\nLast: {}\nSuccess Rate: {}%\nFuel Consumed: {}",
             self.operations.load(Ordering::SeqCst),
             self.last_operation,
-            self.monitor.get_success_rate()
-        )
+            self.monitor.get_success_rate(),
+            self.last_fuel_consumed,
+        );
+        #[cfg(feature = "jit")]
+        {
+            stats.push_str(&format!(
+                "\nJIT Cache Hit Ratio: {:.3}",
+                self.jit_cache.hit_ratio()
+            ));
+        }
+        stats
+    }
+}
+
+impl WasmInterpreter {
+    /// Register a host function callable from guest code by `name`.
+    ///
+    /// This is the hook the `OperationMonitor`/logging layer uses to call
+    /// back into the embedder while a guest module runs.
+    pub fn add_host_function<F>(&mut self, name: &str, f: F)
+    where
+        F: Fn(&[Value]) -> Result<Vec<Value>, Trap> + Send + Sync + 'static,
+    {
+        self.host_functions
+            .lock()
+            .unwrap()
+            .push((name.to_string(), Arc::new(f)));
+    }
+
+    /// Clone the registered host hooks into `instance`. Hooks are `Arc`-backed
+    /// so this can run on every `interpret*` call without emptying the
+    /// registry the way draining the old `Vec<(String, HostFn)>` did.
+    fn install_host_functions(&self, instance: &mut ModuleInstance) {
+        for (name, f) in self.host_functions.lock().unwrap().iter() {
+            let f = f.clone();
+            instance.add_host_function(name, move |args: &[Value]| f(args));
+        }
+    }
+
+    #[cfg(not(feature = "jit"))]
+    fn run(&mut self, hex: &str) -> Result<Vec<Value>, Trap> {
+        let bytes = decode_hex(hex)?;
+        let module = runtime::parse(&bytes)?;
+        let mut instance = ModuleInstance::new(module);
+        self.install_host_functions(&mut instance);
+        instance.invoke(&self.entry_point, &[])
     }
-} 
\ No newline at end of file
+
+    // With `jit` enabled the decoded module is compiled once and reused
+    // from the cache on repeated calls, skipping re-parse/re-instrumentation.
+    #[cfg(feature = "jit")]
+    fn run(&mut self, hex: &str) -> Result<Vec<Value>, Trap> {
+        let bytes = decode_hex(hex)?;
+        let compiled = self.jit_cache.get_or_compile(&bytes, &mut self.metrics)?;
+        let mut instance = ModuleInstance::new(compiled.module.clone());
+        self.install_host_functions(&mut instance);
+        instance.invoke(&self.entry_point, &[])
+    }
+
+    fn run_metered(&mut self, hex: &str, limit: u64) -> Result<(Vec<Value>, u64), Trap> {
+        let bytes = decode_hex(hex)?;
+        let module = runtime::parse(&bytes)?;
+        let plan = fuel::plan(&module);
+        let mut instance = ModuleInstance::new(module);
+        self.install_host_functions(&mut instance);
+        let mut fuel = Fuel::new(limit);
+        instance.set_fuel(std::mem::replace(&mut fuel, Fuel::new(0)), plan);
+        let values = instance.invoke(&self.entry_point, &[])?;
+        let consumed = instance.fuel_consumed();
+        Ok((values, consumed))
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Trap> {
+    if hex.len() % 2 != 0 {
+        return Err(Trap::InvalidModule("odd-length hex".into()));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| Trap::InvalidModule("non-hex digit".into()))
+        })
+        .collect()
+}
+
+fn encode_results(values: &[Value]) -> JsValue {
+    match values.first() {
+        Some(Value::I32(v)) => JsValue::from_f64(*v as f64),
+        Some(Value::I64(v)) => JsValue::from_f64(*v as f64),
+        Some(Value::F32(v)) => JsValue::from_f64(*v as f64),
+        Some(Value::F64(v)) => JsValue::from_f64(*v),
+        None => JsValue::UNDEFINED,
+    }
+}
@@ -0,0 +1,75 @@
+//! Optional compiled-module cache, gated behind the `jit` feature.
+//!
+//! Parsing and fuel-instrumenting a module on every [`interpret`] call is
+//! wasteful when the same hex program runs repeatedly. With `jit` enabled
+//! a decoded module is compiled once and the compiled artifact is stored in
+//! the crate's [`Cache`] keyed by a hash of the decoded module bytes, so subsequent
+//! calls on the same program skip re-parsing and re-instrumentation.
+//!
+//! [`interpret`]: crate::wasm::interpreter::WasmInterpreter::interpret
+//! [`Cache`]: crate::core::cache::Cache
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::core::cache::Cache;
+use crate::core::metrics::PerformanceMetrics;
+use crate::wasm::fuel::{self, FuelPlan};
+use crate::wasm::runtime::{self, Module, Trap};
+
+/// A parsed module plus its fuel plan, ready to instantiate.
+pub struct CompiledModule {
+    pub module: Module,
+    pub plan: FuelPlan,
+}
+
+/// A compiled-module cache over the shared [`Cache`] LRU/TTL store.
+///
+/// Cloning a cached `Arc<CompiledModule>` is cheap; the underlying
+/// `Cache` eviction (LRU + TTL) drops stale artifacts automatically, and
+/// because the key is a hash of the module bytes a changed program simply
+/// misses and compiles fresh.
+pub struct JitCache {
+    cache: Cache<Arc<CompiledModule>>,
+}
+
+impl JitCache {
+    pub fn new(max_size: usize, ttl_seconds: u64) -> Self {
+        Self {
+            cache: Cache::new(max_size, ttl_seconds),
+        }
+    }
+
+    /// Fetch the compiled form of `bytes`, compiling and inserting on a
+    /// miss. Cache hits/misses are recorded into `metrics`.
+    pub fn get_or_compile(
+        &mut self,
+        bytes: &[u8],
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<Arc<CompiledModule>, Trap> {
+        let key = hash_key(bytes);
+        if let Some(compiled) = self.cache.get(&key) {
+            metrics.record_cache_hit();
+            return Ok(compiled);
+        }
+
+        metrics.record_cache_miss();
+        let module = runtime::parse(bytes)?;
+        let plan = fuel::plan(&module);
+        let compiled = Arc::new(CompiledModule { module, plan });
+        self.cache.insert(key, compiled.clone());
+        Ok(compiled)
+    }
+
+    /// Hit ratio of the compiled-module cache, for `get_stats`.
+    pub fn hit_ratio(&self) -> f64 {
+        self.cache.stats().hit_ratio
+    }
+}
+
+fn hash_key(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
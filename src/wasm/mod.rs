@@ -1,3 +1,8 @@
+pub mod fuel;
+#[cfg(feature = "jit")]
+pub mod jit;
+pub mod runtime;
+
 use wasm_bindgen::prelude::*;
 use crate::interpreter::evaluator::Evaluator;
 use crate::compiler::lexer::Lexer;
@@ -23,11 +28,25 @@ impl LumaScript {
         let lexer = Lexer::new(code);
         let mut parser = Parser::new(lexer);
         let ast = parser.parse()?;
-        
+
         let result = self.evaluator.eval(&ast)?;
         Ok(format!("{:?}", result))
     }
 
+    /// Lint `code` and return the diagnostics as a JSON value so the
+    /// playground can render them. Parse errors yield an empty list.
+    pub fn lint(&self, code: &str) -> JsValue {
+        let diagnostics = self.lint_inner(code);
+        JsValue::from_serde(&diagnostics).unwrap_or(JsValue::NULL)
+    }
+
+    /// Lint `code` and apply every non-overlapping autofix, returning the
+    /// rewritten source (unchanged when there is nothing to fix).
+    pub fn fix(&self, code: &str) -> String {
+        let diagnostics = self.lint_inner(code);
+        crate::compiler::lint::apply_fixes(code, &diagnostics)
+    }
+
     pub fn get_cache_stats(&self) -> JsValue {
         let (ast_stats, value_stats) = self.evaluator.get_cache_stats();
         let metrics = self.evaluator.get_performance_metrics();
@@ -53,4 +72,17 @@ impl LumaScript {
 
         JsValue::from_serde(&stats).unwrap()
     }
+}
+
+impl LumaScript {
+    /// Parse `code` and run the default linter; on a parse error there is
+    /// nothing to lint, so return no diagnostics.
+    fn lint_inner(&self, code: &str) -> Vec<crate::compiler::lint::Diagnostic> {
+        let lexer = Lexer::new(code);
+        let mut parser = Parser::new(lexer);
+        match parser.parse() {
+            Ok(program) => crate::compiler::lint::Linter::new().run(&program, code),
+            Err(_) => Vec::new(),
+        }
+    }
 } 
\ No newline at end of file
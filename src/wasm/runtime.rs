@@ -0,0 +1,975 @@
+//! A small, self-contained WebAssembly runtime.
+//!
+//! This is a pure-Rust stack machine: the binary is parsed into a
+//! [`Module`], validated lightly, instantiated into a [`ModuleInstance`]
+//! (linear memory and globals) and then executed by walking the opcode
+//! stream with an operand stack and a call stack. Tables are not modelled,
+//! so there is no function table and `call_indirect` is not supported.
+//!
+//! Only the subset of the spec the crate actually exercises is
+//! implemented — the four numeric value types, local/global access,
+//! structured control flow, calls (direct and to host functions) and
+//! linear-memory load/store. Anything unsupported surfaces as a
+//! [`Trap`] rather than a panic so callers can map it onto `Err(JsValue)`.
+
+use std::collections::HashMap;
+
+/// A runtime value. Mirrors the four WebAssembly numeric types.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+}
+
+impl Value {
+    fn ty(&self) -> ValType {
+        match self {
+            Value::I32(_) => ValType::I32,
+            Value::I64(_) => ValType::I64,
+            Value::F32(_) => ValType::F32,
+            Value::F64(_) => ValType::F64,
+        }
+    }
+
+    fn as_i32(&self) -> Result<i32, Trap> {
+        match self {
+            Value::I32(v) => Ok(*v),
+            other => Err(Trap::TypeMismatch {
+                expected: ValType::I32,
+                found: other.ty(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+/// A trap raised during parsing, validation or execution.
+///
+/// Every failure mode the runtime can hit is enumerated here so the
+/// embedder can render a stable message; `interpret` converts these into
+/// `Err(JsValue)`.
+#[derive(Debug, Clone)]
+pub enum Trap {
+    Unreachable,
+    MemoryOutOfBounds { offset: usize, len: usize },
+    TypeMismatch { expected: ValType, found: ValType },
+    StackUnderflow,
+    UndefinedFunction(String),
+    InvalidModule(String),
+    CallDepthExceeded,
+    /// Fuel metering hit zero. Distinct from `Unreachable` so a runaway loop
+    /// killed by the fuel limit doesn't read as a guest `unreachable`.
+    OutOfFuel,
+}
+
+impl std::fmt::Display for Trap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trap::Unreachable => write!(f, "unreachable executed"),
+            Trap::MemoryOutOfBounds { offset, len } => {
+                write!(f, "out-of-bounds memory access at {offset} (len {len})")
+            }
+            Trap::TypeMismatch { expected, found } => {
+                write!(f, "type mismatch: expected {expected:?}, found {found:?}")
+            }
+            Trap::StackUnderflow => write!(f, "operand stack underflow"),
+            Trap::UndefinedFunction(name) => write!(f, "undefined function `{name}`"),
+            Trap::InvalidModule(msg) => write!(f, "invalid module: {msg}"),
+            Trap::CallDepthExceeded => write!(f, "call stack depth exceeded"),
+            Trap::OutOfFuel => write!(f, "out of fuel"),
+        }
+    }
+}
+
+impl std::error::Error for Trap {}
+
+/// A boxed host function callable from guest code.
+pub type HostFn = Box<dyn Fn(&[Value]) -> Result<Vec<Value>, Trap> + Send + Sync>;
+
+/// A parsed, not-yet-instantiated module.
+#[derive(Clone)]
+pub struct Module {
+    pub(crate) types: Vec<FuncType>,
+    pub(crate) functions: Vec<Function>,
+    pub(crate) exports: HashMap<String, usize>,
+    pub(crate) globals: Vec<Value>,
+    pub(crate) memory_pages: u32,
+    pub(crate) imports: Vec<Import>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FuncType {
+    pub(crate) params: Vec<ValType>,
+    pub(crate) results: Vec<ValType>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Function {
+    pub(crate) ty: usize,
+    pub(crate) locals: Vec<ValType>,
+    pub(crate) body: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Import {
+    pub(crate) module: String,
+    pub(crate) name: String,
+    pub(crate) ty: usize,
+}
+
+const PAGE_SIZE: usize = 64 * 1024;
+
+/// An instantiated module: code plus the mutable state it runs against.
+pub struct ModuleInstance {
+    module: Module,
+    memory: Vec<u8>,
+    globals: Vec<Value>,
+    host_functions: HashMap<String, HostFn>,
+    max_call_depth: usize,
+    fuel: Option<crate::wasm::fuel::Fuel>,
+    fuel_plan: crate::wasm::fuel::FuelPlan,
+}
+
+impl ModuleInstance {
+    /// Instantiate `module`, allocating its declared linear memory and
+    /// copying its global initializers.
+    pub fn new(module: Module) -> Self {
+        let memory = vec![0u8; module.memory_pages as usize * PAGE_SIZE];
+        let globals = module.globals.clone();
+        Self {
+            module,
+            memory,
+            globals,
+            host_functions: HashMap::new(),
+            max_call_depth: 1024,
+            fuel: None,
+            fuel_plan: crate::wasm::fuel::FuelPlan::default(),
+        }
+    }
+
+    /// Enable fuel metering for this instance, seeding the counter and the
+    /// precomputed per-block cost plan. When set, [`exec`](Self::exec) charges
+    /// a block's cost as `pc` reaches it and traps once the counter goes
+    /// negative.
+    pub fn set_fuel(&mut self, fuel: crate::wasm::fuel::Fuel, plan: crate::wasm::fuel::FuelPlan) {
+        self.fuel = Some(fuel);
+        self.fuel_plan = plan;
+    }
+
+    /// Total fuel consumed since metering was enabled.
+    pub fn fuel_consumed(&self) -> u64 {
+        self.fuel.as_ref().map(|f| f.consumed()).unwrap_or(0)
+    }
+
+    /// Register a host function the guest may import under `name`.
+    pub fn add_host_function<F>(&mut self, name: &str, f: F)
+    where
+        F: Fn(&[Value]) -> Result<Vec<Value>, Trap> + Send + Sync + 'static,
+    {
+        self.host_functions.insert(name.to_string(), Box::new(f));
+    }
+
+    /// Invoke the exported function `name` with `args`, returning its
+    /// result values.
+    pub fn invoke(&mut self, name: &str, args: &[Value]) -> Result<Vec<Value>, Trap> {
+        let idx = *self
+            .module
+            .exports
+            .get(name)
+            .ok_or_else(|| Trap::UndefinedFunction(name.to_string()))?;
+        // `idx` is a function-index-space index: imports occupy the low
+        // indices, so it must be rebased onto `module.functions` the same
+        // way `dispatch_call` does for calls made from guest code.
+        let real_idx = idx
+            .checked_sub(self.module.imports.len())
+            .ok_or_else(|| Trap::UndefinedFunction(name.to_string()))?;
+        self.call(real_idx, args.to_vec(), 0)
+    }
+
+    fn call(&mut self, func_idx: usize, args: Vec<Value>, depth: usize) -> Result<Vec<Value>, Trap> {
+        if depth >= self.max_call_depth {
+            return Err(Trap::CallDepthExceeded);
+        }
+        let func = self
+            .module
+            .functions
+            .get(func_idx)
+            .cloned()
+            .ok_or_else(|| Trap::UndefinedFunction(format!("#{func_idx}")))?;
+
+        let ty = self.module.types[func.ty].clone();
+        if args.len() != ty.params.len() {
+            return Err(Trap::TypeMismatch {
+                expected: *ty.params.first().unwrap_or(&ValType::I32),
+                found: args.first().map(|v| v.ty()).unwrap_or(ValType::I32),
+            });
+        }
+
+        // Locals = params followed by zero-initialized declared locals.
+        let mut locals = args;
+        for lt in &func.locals {
+            locals.push(match lt {
+                ValType::I32 => Value::I32(0),
+                ValType::I64 => Value::I64(0),
+                ValType::F32 => Value::F32(0.0),
+                ValType::F64 => Value::F64(0.0),
+            });
+        }
+
+        let mut frame = Frame {
+            locals,
+            stack: Vec::new(),
+        };
+        self.exec(func_idx, &func.body, &mut frame, depth)?;
+
+        let mut results = Vec::with_capacity(ty.results.len());
+        for _ in 0..ty.results.len() {
+            results.push(frame.stack.pop().ok_or(Trap::StackUnderflow)?);
+        }
+        results.reverse();
+        Ok(results)
+    }
+
+    fn exec(
+        &mut self,
+        func_idx: usize,
+        body: &[u8],
+        frame: &mut Frame,
+        depth: usize,
+    ) -> Result<(), Trap> {
+        let blocks = scan_blocks(body)?;
+        let mut labels: Vec<Label> = Vec::new();
+        let mut pc = 0usize;
+        while pc < body.len() {
+            // Charge the metered block starting at `pc`, if any. This fires
+            // exactly when control reaches a block — on function entry, on
+            // fallthrough into the next block, on a branch, and on every
+            // loop back-edge — so only blocks actually taken are charged.
+            let block_cost = self
+                .fuel_plan
+                .block_costs
+                .get(func_idx)
+                .and_then(|costs| costs.get(&pc))
+                .copied();
+            if let Some(cost) = block_cost {
+                if let Some(fuel) = self.fuel.as_mut() {
+                    fuel.charge(cost)?;
+                }
+            }
+
+            let op = body[pc];
+            let op_start = pc;
+            pc += 1;
+            match op {
+                op::UNREACHABLE => return Err(Trap::Unreachable),
+                op::NOP => {}
+                op::RETURN => return Ok(()),
+                op::BLOCK => {
+                    let bt = read_sleb(body, &mut pc)?;
+                    let (_, results) = self.block_arity(bt)?;
+                    let end = *blocks
+                        .ends
+                        .get(&op_start)
+                        .ok_or_else(|| Trap::InvalidModule("unmatched block".into()))?;
+                    labels.push(Label {
+                        kind: LabelKind::Block,
+                        arity: results,
+                        height: frame.stack.len(),
+                        cont: end + 1,
+                        start: pc,
+                    });
+                }
+                op::LOOP => {
+                    let bt = read_sleb(body, &mut pc)?;
+                    let (params, _) = self.block_arity(bt)?;
+                    let end = *blocks
+                        .ends
+                        .get(&op_start)
+                        .ok_or_else(|| Trap::InvalidModule("unmatched loop".into()))?;
+                    labels.push(Label {
+                        kind: LabelKind::Loop,
+                        arity: params,
+                        height: frame.stack.len(),
+                        cont: end + 1,
+                        start: pc,
+                    });
+                }
+                op::IF => {
+                    let bt = read_sleb(body, &mut pc)?;
+                    let (_, results) = self.block_arity(bt)?;
+                    let cond = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    let end = *blocks
+                        .ends
+                        .get(&op_start)
+                        .ok_or_else(|| Trap::InvalidModule("unmatched if".into()))?;
+                    labels.push(Label {
+                        kind: LabelKind::Block,
+                        arity: results,
+                        height: frame.stack.len(),
+                        cont: end + 1,
+                        start: pc,
+                    });
+                    if cond == 0 {
+                        // Skip the then-branch: jump to the else body if there
+                        // is one, otherwise straight to the matching `end`.
+                        match blocks.elses.get(&op_start) {
+                            Some(&el) => pc = el + 1,
+                            None => pc = end,
+                        }
+                    }
+                }
+                op::ELSE => {
+                    // Reached only at the end of a taken then-branch; skip the
+                    // else body by jumping past the matching `end`.
+                    let label = labels.pop().ok_or_else(|| {
+                        Trap::InvalidModule("else without matching if".into())
+                    })?;
+                    pc = label.cont;
+                }
+                op::END => {
+                    if labels.pop().is_none() {
+                        return Ok(());
+                    }
+                }
+                op::BR => {
+                    let n = read_uleb(body, &mut pc)? as usize;
+                    self.branch(n, &mut labels, frame, &mut pc)?;
+                }
+                op::BR_IF => {
+                    let n = read_uleb(body, &mut pc)? as usize;
+                    let cond = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    if cond != 0 {
+                        self.branch(n, &mut labels, frame, &mut pc)?;
+                    }
+                }
+                op::BR_TABLE => {
+                    let count = read_uleb(body, &mut pc)? as usize;
+                    let mut targets = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        targets.push(read_uleb(body, &mut pc)? as usize);
+                    }
+                    let default = read_uleb(body, &mut pc)? as usize;
+                    let idx = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()? as usize;
+                    let n = targets.get(idx).copied().unwrap_or(default);
+                    self.branch(n, &mut labels, frame, &mut pc)?;
+                }
+                op::LOCAL_GET => {
+                    let i = read_uleb(body, &mut pc)? as usize;
+                    let v = *frame.locals.get(i).ok_or(Trap::StackUnderflow)?;
+                    frame.stack.push(v);
+                }
+                op::LOCAL_SET => {
+                    let i = read_uleb(body, &mut pc)? as usize;
+                    let v = frame.stack.pop().ok_or(Trap::StackUnderflow)?;
+                    *frame.locals.get_mut(i).ok_or(Trap::StackUnderflow)? = v;
+                }
+                op::GLOBAL_GET => {
+                    let i = read_uleb(body, &mut pc)? as usize;
+                    let v = *self.globals.get(i).ok_or(Trap::StackUnderflow)?;
+                    frame.stack.push(v);
+                }
+                op::GLOBAL_SET => {
+                    let i = read_uleb(body, &mut pc)? as usize;
+                    let v = frame.stack.pop().ok_or(Trap::StackUnderflow)?;
+                    *self.globals.get_mut(i).ok_or(Trap::StackUnderflow)? = v;
+                }
+                op::I32_CONST => {
+                    let v = read_sleb(body, &mut pc)? as i32;
+                    frame.stack.push(Value::I32(v));
+                }
+                op::I64_CONST => {
+                    let v = read_sleb(body, &mut pc)?;
+                    frame.stack.push(Value::I64(v));
+                }
+                op::I32_ADD => self.bin_i32(frame, |a, b| a.wrapping_add(b))?,
+                op::I32_SUB => self.bin_i32(frame, |a, b| a.wrapping_sub(b))?,
+                op::I32_MUL => self.bin_i32(frame, |a, b| a.wrapping_mul(b))?,
+                op::I32_AND => self.bin_i32(frame, |a, b| a & b)?,
+                op::I32_OR => self.bin_i32(frame, |a, b| a | b)?,
+                op::I32_XOR => self.bin_i32(frame, |a, b| a ^ b)?,
+                op::I32_EQ => self.bin_i32(frame, |a, b| (a == b) as i32)?,
+                op::I32_NE => self.bin_i32(frame, |a, b| (a != b) as i32)?,
+                op::I32_LT_S => self.bin_i32(frame, |a, b| (a < b) as i32)?,
+                op::I32_GT_S => self.bin_i32(frame, |a, b| (a > b) as i32)?,
+                op::I32_LE_S => self.bin_i32(frame, |a, b| (a <= b) as i32)?,
+                op::I32_GE_S => self.bin_i32(frame, |a, b| (a >= b) as i32)?,
+                op::I32_EQZ => {
+                    let v = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    frame.stack.push(Value::I32((v == 0) as i32));
+                }
+                op::I32_LOAD => {
+                    let _align = read_uleb(body, &mut pc)?;
+                    let off = read_uleb(body, &mut pc)? as usize;
+                    let base = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    let addr = effective_addr(base, off, 4)?;
+                    let v = self.load_i32(addr)?;
+                    frame.stack.push(Value::I32(v));
+                }
+                op::I32_STORE => {
+                    let _align = read_uleb(body, &mut pc)?;
+                    let off = read_uleb(body, &mut pc)? as usize;
+                    let v = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    let base = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+                    let addr = effective_addr(base, off, 4)?;
+                    self.store_i32(addr, v)?;
+                }
+                op::CALL => {
+                    let callee = read_uleb(body, &mut pc)? as usize;
+                    self.dispatch_call(callee, frame, depth)?;
+                }
+                other => {
+                    return Err(Trap::InvalidModule(format!(
+                        "unsupported opcode 0x{other:02x}"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve a block type (sleb) into its `(params, results)` arity. An
+    /// empty block type has no values; a negative encoding is a single result
+    /// value; a non-negative index references a function type.
+    fn block_arity(&self, bt: i64) -> Result<(usize, usize), Trap> {
+        if bt == -0x40 {
+            Ok((0, 0))
+        } else if bt < 0 {
+            Ok((0, 1))
+        } else {
+            let ty = self
+                .module
+                .types
+                .get(bt as usize)
+                .ok_or_else(|| Trap::InvalidModule("bad block type".into()))?;
+            Ok((ty.params.len(), ty.results.len()))
+        }
+    }
+
+    /// Branch to the label `n` levels out. For a `loop` this re-enters the
+    /// body (a back-edge) — the per-block fuel charge in [`exec`](Self::exec)
+    /// re-fires once `pc` lands back on the loop's start offset, so iterations
+    /// keep draining fuel without a separate charge here; for a `block`/`if`
+    /// it exits to the matching `end`. The values feeding the target label
+    /// are preserved across the operand-stack unwind.
+    fn branch(
+        &mut self,
+        n: usize,
+        labels: &mut Vec<Label>,
+        frame: &mut Frame,
+        pc: &mut usize,
+    ) -> Result<(), Trap> {
+        let idx = labels
+            .len()
+            .checked_sub(1 + n)
+            .ok_or_else(|| Trap::InvalidModule("branch depth out of range".into()))?;
+        let label = labels[idx].clone();
+
+        // Preserve the values the target expects, discard the rest.
+        let mut kept = Vec::with_capacity(label.arity);
+        for _ in 0..label.arity {
+            kept.push(frame.stack.pop().ok_or(Trap::StackUnderflow)?);
+        }
+        frame.stack.truncate(label.height);
+        while let Some(v) = kept.pop() {
+            frame.stack.push(v);
+        }
+
+        match label.kind {
+            LabelKind::Loop => {
+                labels.truncate(idx + 1);
+                *pc = label.start;
+            }
+            LabelKind::Block => {
+                labels.truncate(idx);
+                *pc = label.cont;
+            }
+        }
+        Ok(())
+    }
+
+    fn dispatch_call(&mut self, callee: usize, frame: &mut Frame, depth: usize) -> Result<(), Trap> {
+        // Imported functions occupy the low indices and resolve to host hooks.
+        if let Some(import) = self.module.imports.get(callee) {
+            let ty = self.module.types[import.ty].clone();
+            let mut args = Vec::with_capacity(ty.params.len());
+            for _ in 0..ty.params.len() {
+                args.push(frame.stack.pop().ok_or(Trap::StackUnderflow)?);
+            }
+            args.reverse();
+            let host = self
+                .host_functions
+                .get(&import.name)
+                .ok_or_else(|| Trap::UndefinedFunction(import.name.clone()))?;
+            for r in host(&args)? {
+                frame.stack.push(r);
+            }
+            return Ok(());
+        }
+
+        let real_idx = callee - self.module.imports.len();
+        let ty = self.module.types[self.module.functions[real_idx].ty].clone();
+        let mut args = Vec::with_capacity(ty.params.len());
+        for _ in 0..ty.params.len() {
+            args.push(frame.stack.pop().ok_or(Trap::StackUnderflow)?);
+        }
+        args.reverse();
+        for r in self.call(real_idx, args, depth + 1)? {
+            frame.stack.push(r);
+        }
+        Ok(())
+    }
+
+    fn bin_i32<F: Fn(i32, i32) -> i32>(&self, frame: &mut Frame, f: F) -> Result<(), Trap> {
+        let b = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+        let a = frame.stack.pop().ok_or(Trap::StackUnderflow)?.as_i32()?;
+        frame.stack.push(Value::I32(f(a, b)));
+        Ok(())
+    }
+
+    fn load_i32(&self, addr: usize) -> Result<i32, Trap> {
+        let end = addr
+            .checked_add(4)
+            .ok_or(Trap::MemoryOutOfBounds { offset: addr, len: 4 })?;
+        let bytes = self
+            .memory
+            .get(addr..end)
+            .ok_or(Trap::MemoryOutOfBounds { offset: addr, len: 4 })?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn store_i32(&mut self, addr: usize, v: i32) -> Result<(), Trap> {
+        let end = addr
+            .checked_add(4)
+            .ok_or(Trap::MemoryOutOfBounds { offset: addr, len: 4 })?;
+        let slot = self
+            .memory
+            .get_mut(addr..end)
+            .ok_or(Trap::MemoryOutOfBounds { offset: addr, len: 4 })?;
+        slot.copy_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    /// Borrow the parsed module, e.g. for fuel instrumentation.
+    pub(crate) fn module_mut(&mut self) -> &mut Module {
+        &mut self.module
+    }
+}
+
+struct Frame {
+    locals: Vec<Value>,
+    stack: Vec<Value>,
+}
+
+#[derive(Clone, Copy)]
+enum LabelKind {
+    Block,
+    Loop,
+}
+
+/// A control-flow label pushed when entering a structured region. A branch
+/// targeting it unwinds the operand stack to `height` and jumps to `start`
+/// (loop back-edge) or `cont` (block/if exit).
+#[derive(Clone)]
+struct Label {
+    kind: LabelKind,
+    arity: usize,
+    height: usize,
+    cont: usize,
+    start: usize,
+}
+
+/// Opcode constants for the supported subset.
+mod op {
+    pub const UNREACHABLE: u8 = 0x00;
+    pub const NOP: u8 = 0x01;
+    pub const BLOCK: u8 = 0x02;
+    pub const LOOP: u8 = 0x03;
+    pub const IF: u8 = 0x04;
+    pub const ELSE: u8 = 0x05;
+    pub const END: u8 = 0x0b;
+    pub const BR: u8 = 0x0c;
+    pub const BR_IF: u8 = 0x0d;
+    pub const BR_TABLE: u8 = 0x0e;
+    pub const RETURN: u8 = 0x0f;
+    pub const CALL: u8 = 0x10;
+    pub const LOCAL_GET: u8 = 0x20;
+    pub const LOCAL_SET: u8 = 0x21;
+    pub const GLOBAL_GET: u8 = 0x23;
+    pub const GLOBAL_SET: u8 = 0x24;
+    pub const I32_LOAD: u8 = 0x28;
+    pub const I32_STORE: u8 = 0x36;
+    pub const I32_CONST: u8 = 0x41;
+    pub const I64_CONST: u8 = 0x42;
+    pub const I32_EQZ: u8 = 0x45;
+    pub const I32_EQ: u8 = 0x46;
+    pub const I32_NE: u8 = 0x47;
+    pub const I32_LT_S: u8 = 0x48;
+    pub const I32_GT_S: u8 = 0x4a;
+    pub const I32_LE_S: u8 = 0x4c;
+    pub const I32_GE_S: u8 = 0x4e;
+    pub const I32_ADD: u8 = 0x6a;
+    pub const I32_SUB: u8 = 0x6b;
+    pub const I32_MUL: u8 = 0x6c;
+    pub const I32_AND: u8 = 0x71;
+    pub const I32_OR: u8 = 0x72;
+    pub const I32_XOR: u8 = 0x73;
+}
+
+/// Advance `pc` past the opcode at `pc` and any immediate operands it
+/// carries, returning the opcode. Shared by the block pre-scanner and the
+/// fuel planner so both agree on instruction boundaries.
+pub(crate) fn decode_instr(body: &[u8], pc: &mut usize) -> Result<u8, Trap> {
+    let opcode = *body
+        .get(*pc)
+        .ok_or_else(|| Trap::InvalidModule("truncated code".into()))?;
+    *pc += 1;
+    match opcode {
+        op::BLOCK | op::LOOP | op::IF => {
+            read_sleb(body, pc)?;
+        }
+        op::BR | op::BR_IF | op::CALL | op::LOCAL_GET | op::LOCAL_SET | op::GLOBAL_GET
+        | op::GLOBAL_SET => {
+            read_uleb(body, pc)?;
+        }
+        op::BR_TABLE => {
+            let count = read_uleb(body, pc)?;
+            for _ in 0..=count {
+                read_uleb(body, pc)?;
+            }
+        }
+        op::I32_CONST | op::I64_CONST => {
+            read_sleb(body, pc)?;
+        }
+        op::I32_LOAD | op::I32_STORE => {
+            read_uleb(body, pc)?; // align
+            read_uleb(body, pc)?; // offset
+        }
+        _ => {}
+    }
+    Ok(opcode)
+}
+
+/// Matching `else`/`end` positions for every structured opcode in a body,
+/// precomputed so branches can jump without rescanning.
+pub(crate) struct BlockTable {
+    /// Structured-opcode position → position of its matching `end`.
+    pub ends: HashMap<usize, usize>,
+    /// `if` position → position of its matching `else`, when present.
+    pub elses: HashMap<usize, usize>,
+}
+
+/// Pre-scan a function body, pairing each `block`/`loop`/`if` with its
+/// matching `else`/`end`.
+pub(crate) fn scan_blocks(body: &[u8]) -> Result<BlockTable, Trap> {
+    let mut ends = HashMap::new();
+    let mut elses = HashMap::new();
+    let mut stack: Vec<usize> = Vec::new();
+    let mut pc = 0;
+    while pc < body.len() {
+        let start = pc;
+        let opcode = decode_instr(body, &mut pc)?;
+        match opcode {
+            op::BLOCK | op::LOOP | op::IF => stack.push(start),
+            op::ELSE => {
+                if let Some(&open) = stack.last() {
+                    elses.insert(open, start);
+                }
+            }
+            op::END => {
+                if let Some(open) = stack.pop() {
+                    ends.insert(open, start);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(BlockTable { ends, elses })
+}
+
+/// Fold a load/store's base address and static offset into an effective
+/// memory address. WASM addresses are unsigned `u32`, so `base` is cast
+/// through `u32` rather than sign-extended, and the addition is checked so
+/// a crafted negative/huge address traps instead of panicking (debug) or
+/// silently wrapping into an in-bounds address (release). `size` is the
+/// access width, used only to report a precise trap.
+fn effective_addr(base: i32, offset: usize, size: usize) -> Result<usize, Trap> {
+    let base = base as u32 as usize;
+    base.checked_add(offset)
+        .ok_or(Trap::MemoryOutOfBounds { offset: base, len: size })
+}
+
+fn read_uleb(buf: &[u8], pc: &mut usize) -> Result<u64, Trap> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *buf
+            .get(*pc)
+            .ok_or_else(|| Trap::InvalidModule("truncated LEB128".into()))?;
+        *pc += 1;
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok(result)
+}
+
+fn read_sleb(buf: &[u8], pc: &mut usize) -> Result<i64, Trap> {
+    let mut result = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = *buf
+            .get(*pc)
+            .ok_or_else(|| Trap::InvalidModule("truncated LEB128".into()))?;
+        *pc += 1;
+        result |= i64::from(byte & 0x7f) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -(1i64 << shift);
+            }
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// Parse a WebAssembly binary into a [`Module`].
+///
+/// Reads the magic number and version header, then walks the section
+/// table. Sections the runtime does not model are skipped by length.
+pub fn parse(bytes: &[u8]) -> Result<Module, Trap> {
+    if bytes.len() < 8 || &bytes[0..4] != b"\0asm" {
+        return Err(Trap::InvalidModule("bad magic".into()));
+    }
+    let mut pc = 8;
+    let mut module = Module {
+        types: Vec::new(),
+        functions: Vec::new(),
+        exports: HashMap::new(),
+        globals: Vec::new(),
+        memory_pages: 1,
+        imports: Vec::new(),
+    };
+    let mut func_type_idx: Vec<usize> = Vec::new();
+
+    while pc < bytes.len() {
+        let id = bytes[pc];
+        pc += 1;
+        let size = read_uleb(bytes, &mut pc)? as usize;
+        let section_end = pc + size;
+        match id {
+            1 => {
+                // Type section
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    pc += 1; // 0x60 func marker
+                    let np = read_uleb(bytes, &mut pc)?;
+                    let mut params = Vec::new();
+                    for _ in 0..np {
+                        params.push(read_valtype(bytes, &mut pc)?);
+                    }
+                    let nr = read_uleb(bytes, &mut pc)?;
+                    let mut results = Vec::new();
+                    for _ in 0..nr {
+                        results.push(read_valtype(bytes, &mut pc)?);
+                    }
+                    module.types.push(FuncType { params, results });
+                }
+            }
+            2 => {
+                // Import section. Only function imports occupy guest call
+                // indices (the low end); table/memory/global imports are
+                // parsed for their bytes but not modelled further.
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    let module_name = read_name(bytes, &mut pc)?;
+                    let field = read_name(bytes, &mut pc)?;
+                    let kind = bytes[pc];
+                    pc += 1;
+                    match kind {
+                        0x00 => {
+                            let ty = read_uleb(bytes, &mut pc)? as usize;
+                            module.imports.push(Import {
+                                module: module_name,
+                                name: field,
+                                ty,
+                            });
+                        }
+                        0x01 => {
+                            pc += 1; // element reftype
+                            read_limits(bytes, &mut pc)?;
+                        }
+                        0x02 => {
+                            read_limits(bytes, &mut pc)?;
+                        }
+                        0x03 => {
+                            pc += 2; // valtype + mutability
+                        }
+                        other => {
+                            return Err(Trap::InvalidModule(format!(
+                                "bad import kind 0x{other:02x}"
+                            )))
+                        }
+                    }
+                }
+            }
+            3 => {
+                // Function section: type indices for locally defined funcs.
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    func_type_idx.push(read_uleb(bytes, &mut pc)? as usize);
+                }
+            }
+            5 => {
+                // Memory section: take the minimum size of the last declared
+                // memory as the page count to allocate.
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    let (min, _max) = read_limits(bytes, &mut pc)?;
+                    module.memory_pages = min;
+                }
+            }
+            6 => {
+                // Global section: each global is a value type, a mutability
+                // flag and a constant initializer expression.
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    let _valtype = read_valtype(bytes, &mut pc)?;
+                    pc += 1; // mutability flag
+                    module.globals.push(read_const_expr(bytes, &mut pc)?);
+                }
+            }
+            7 => {
+                // Export section
+                let count = read_uleb(bytes, &mut pc)?;
+                for _ in 0..count {
+                    let name = read_name(bytes, &mut pc)?;
+                    let kind = bytes[pc];
+                    pc += 1;
+                    let idx = read_uleb(bytes, &mut pc)? as usize;
+                    if kind == 0x00 {
+                        module.exports.insert(name, idx);
+                    }
+                }
+            }
+            10 => {
+                // Code section
+                let count = read_uleb(bytes, &mut pc)?;
+                for i in 0..count as usize {
+                    let body_size = read_uleb(bytes, &mut pc)? as usize;
+                    let body_end = pc + body_size;
+                    let local_decls = read_uleb(bytes, &mut pc)?;
+                    let mut locals = Vec::new();
+                    for _ in 0..local_decls {
+                        let n = read_uleb(bytes, &mut pc)?;
+                        let t = read_valtype(bytes, &mut pc)?;
+                        for _ in 0..n {
+                            locals.push(t);
+                        }
+                    }
+                    let body = bytes[pc..body_end].to_vec();
+                    pc = body_end;
+                    let ty = *func_type_idx.get(i).unwrap_or(&0);
+                    module.functions.push(Function { ty, locals, body });
+                }
+            }
+            _ => {}
+        }
+        pc = section_end;
+    }
+    Ok(module)
+}
+
+/// Read a limits descriptor (`flags min [max]`), returning `(min, max)`.
+fn read_limits(bytes: &[u8], pc: &mut usize) -> Result<(u32, Option<u32>), Trap> {
+    let flags = read_uleb(bytes, pc)?;
+    let min = read_uleb(bytes, pc)? as u32;
+    let max = if flags & 0x01 != 0 {
+        Some(read_uleb(bytes, pc)? as u32)
+    } else {
+        None
+    };
+    Ok((min, max))
+}
+
+/// Evaluate a global's constant initializer expression (a single `*.const`
+/// followed by `end`) into its value.
+fn read_const_expr(bytes: &[u8], pc: &mut usize) -> Result<Value, Trap> {
+    let op = *bytes
+        .get(*pc)
+        .ok_or_else(|| Trap::InvalidModule("truncated global init".into()))?;
+    *pc += 1;
+    let value = match op {
+        0x41 => Value::I32(read_sleb(bytes, pc)? as i32),
+        0x42 => Value::I64(read_sleb(bytes, pc)?),
+        0x43 => {
+            let b = bytes
+                .get(*pc..*pc + 4)
+                .ok_or_else(|| Trap::InvalidModule("truncated f32 const".into()))?;
+            *pc += 4;
+            Value::F32(f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        }
+        0x44 => {
+            let b = bytes
+                .get(*pc..*pc + 8)
+                .ok_or_else(|| Trap::InvalidModule("truncated f64 const".into()))?;
+            *pc += 8;
+            Value::F64(f64::from_le_bytes([
+                b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+            ]))
+        }
+        other => {
+            return Err(Trap::InvalidModule(format!(
+                "unsupported global init opcode 0x{other:02x}"
+            )))
+        }
+    };
+    // Consume the terminating `end` (0x0b).
+    let end = *bytes
+        .get(*pc)
+        .ok_or_else(|| Trap::InvalidModule("global init missing end".into()))?;
+    *pc += 1;
+    if end != 0x0b {
+        return Err(Trap::InvalidModule("global init not constant".into()));
+    }
+    Ok(value)
+}
+
+fn read_valtype(bytes: &[u8], pc: &mut usize) -> Result<ValType, Trap> {
+    let b = *bytes
+        .get(*pc)
+        .ok_or_else(|| Trap::InvalidModule("truncated valtype".into()))?;
+    *pc += 1;
+    match b {
+        0x7f => Ok(ValType::I32),
+        0x7e => Ok(ValType::I64),
+        0x7d => Ok(ValType::F32),
+        0x7c => Ok(ValType::F64),
+        other => Err(Trap::InvalidModule(format!("bad valtype 0x{other:02x}"))),
+    }
+}
+
+fn read_name(bytes: &[u8], pc: &mut usize) -> Result<String, Trap> {
+    let len = read_uleb(bytes, pc)? as usize;
+    let end = *pc + len;
+    let s = std::str::from_utf8(
+        bytes
+            .get(*pc..end)
+            .ok_or_else(|| Trap::InvalidModule("truncated name".into()))?,
+    )
+    .map_err(|_| Trap::InvalidModule("non-utf8 name".into()))?
+    .to_string();
+    *pc = end;
+    Ok(s)
+}
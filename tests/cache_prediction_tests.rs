@@ -0,0 +1,36 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use lumascript::core::cache::EnhancedCache;
+
+/// A regular access cadence keeps the EWMA's relative stddev low, so the
+/// predictor trusts the estimate and `predictive_hits` picks the key up.
+#[test]
+fn regular_cadence_is_predicted() {
+    let cache: EnhancedCache<i32> = EnhancedCache::new(4, 60);
+    cache.insert("regular".to_string(), 1);
+
+    for _ in 0..3 {
+        sleep(Duration::from_millis(40));
+        assert_eq!(cache.get("regular"), Some(1));
+    }
+
+    assert_eq!(cache.get_stats().predictive_hits, 1);
+}
+
+/// A bursty cadence (one short, one long interval) blows out the relative
+/// stddev past the confidence threshold, so the gate withholds a prediction.
+#[test]
+fn bursty_cadence_is_not_predicted() {
+    let cache: EnhancedCache<i32> = EnhancedCache::new(4, 60);
+    cache.insert("bursty".to_string(), 1);
+
+    sleep(Duration::from_millis(10));
+    assert_eq!(cache.get("bursty"), Some(1));
+    sleep(Duration::from_millis(300));
+    assert_eq!(cache.get("bursty"), Some(1));
+    sleep(Duration::from_millis(10));
+    assert_eq!(cache.get("bursty"), Some(1));
+
+    assert_eq!(cache.get_stats().predictive_hits, 0);
+}
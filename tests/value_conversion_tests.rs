@@ -0,0 +1,79 @@
+use lumascript::compiler::value::{ConvError, Conversion, Value};
+
+#[test]
+fn conversion_names_parse_including_aliases() {
+    assert_eq!("int".parse::<Conversion>().unwrap(), Conversion::Int);
+    assert_eq!("integer".parse::<Conversion>().unwrap(), Conversion::Int);
+    assert_eq!("float".parse::<Conversion>().unwrap(), Conversion::Float);
+    assert_eq!("bool".parse::<Conversion>().unwrap(), Conversion::Bool);
+    assert_eq!("boolean".parse::<Conversion>().unwrap(), Conversion::Bool);
+    assert_eq!("string".parse::<Conversion>().unwrap(), Conversion::Str);
+    assert_eq!("bytes".parse::<Conversion>().unwrap(), Conversion::Bytes);
+    assert_eq!("timestamp".parse::<Conversion>().unwrap(), Conversion::Timestamp);
+    assert_eq!(
+        "timestamp_fmt:%Y-%m-%d".parse::<Conversion>().unwrap(),
+        Conversion::TimestampFmt("%Y-%m-%d".to_string())
+    );
+
+    assert_eq!(
+        "nonsense".parse::<Conversion>().unwrap_err(),
+        ConvError::UnknownConversion("nonsense".to_string())
+    );
+}
+
+#[test]
+fn string_to_int_trims_whitespace_and_rejects_garbage() {
+    let conv: Conversion = "int".parse().unwrap();
+    assert_eq!(
+        Value::Str(" 42 ".to_string()).convert(&conv).unwrap(),
+        Value::Int(42)
+    );
+    assert!(Value::Str("not a number".to_string()).convert(&conv).is_err());
+}
+
+#[test]
+fn bool_accepts_common_spellings_case_insensitively() {
+    let conv: Conversion = "bool".parse().unwrap();
+    assert_eq!(Value::Str("TRUE".to_string()).convert(&conv).unwrap(), Value::Bool(true));
+    assert_eq!(Value::Str("yes".to_string()).convert(&conv).unwrap(), Value::Bool(true));
+    assert_eq!(Value::Str("0".to_string()).convert(&conv).unwrap(), Value::Bool(false));
+    assert!(Value::Str("maybe".to_string()).convert(&conv).is_err());
+}
+
+#[test]
+fn bytes_are_not_coercible_to_numeric_types() {
+    let bytes = Value::Bytes(vec![1, 2, 3]);
+    assert!(bytes.convert(&"int".parse().unwrap()).is_err());
+    assert!(bytes.convert(&"float".parse().unwrap()).is_err());
+    assert!(bytes.convert(&"bool".parse().unwrap()).is_err());
+}
+
+#[test]
+fn int_round_trips_through_epoch_millis_timestamp() {
+    let conv: Conversion = "timestamp".parse().unwrap();
+    let as_ts = Value::Int(0).convert(&conv).unwrap();
+    let back = as_ts.convert(&"int".parse().unwrap()).unwrap();
+    assert_eq!(back, Value::Int(0));
+}
+
+#[test]
+fn timestamp_parses_rfc3339_and_custom_format() {
+    let rfc3339: Conversion = "timestamp".parse().unwrap();
+    assert!(Value::Str("2024-01-01T00:00:00Z".to_string())
+        .convert(&rfc3339)
+        .is_ok());
+    assert!(Value::Str("not a date".to_string()).convert(&rfc3339).is_err());
+
+    let custom: Conversion = "timestamp_fmt:%Y-%m-%d".parse().unwrap();
+    assert!(Value::Str("2024-01-01".to_string()).convert(&custom).is_ok());
+    assert!(Value::Str("2024-01-01T00:00:00Z".to_string())
+        .convert(&custom)
+        .is_err());
+}
+
+#[test]
+fn str_conversion_stringifies_every_variant() {
+    let conv: Conversion = "string".parse().unwrap();
+    assert_eq!(Value::Int(7).convert(&conv).unwrap(), Value::Str("7".to_string()));
+    assert_eq!(Value::Bool(true).convert(&conv).unwrap(), Value::Str("true".to_string()));
+}
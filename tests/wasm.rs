@@ -15,4 +15,48 @@ fn test_runtime() {
         runtime.print("test"),
         "LumaScript: test"
     );
-} 
\ No newline at end of file
+}
+
+#[wasm_bindgen_test]
+fn test_signer_sign_verify_round_trip() {
+    let signer = LumaSigner::new();
+    let generated = signer.generate().unwrap();
+    let parts: Vec<&str> = generated.split(':').collect();
+    let (secret_hex, public_hex, address_hex) = (parts[0], parts[1], parts[2]);
+
+    let message_hex = "48656c6c6f"; // "Hello"
+    let signature_hex = signer.sign(secret_hex, message_hex).unwrap();
+
+    assert!(signer
+        .verify_public(public_hex, &signature_hex, message_hex)
+        .unwrap());
+    assert!(signer
+        .verify_address(address_hex, &signature_hex, message_hex)
+        .unwrap());
+    assert_eq!(
+        signer.recover_public(&signature_hex, message_hex).unwrap(),
+        public_hex
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_signer_rejects_signature_over_wrong_message() {
+    let signer = LumaSigner::new();
+    let generated = signer.generate().unwrap();
+    let secret_hex = generated.split(':').next().unwrap();
+
+    let signature_hex = signer.sign(secret_hex, "48656c6c6f").unwrap();
+    assert!(!signer
+        .verify_public(
+            &generated.split(':').nth(1).unwrap(),
+            &signature_hex,
+            "676f6f646279" // "goodby" — a different message
+        )
+        .unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_signer_rejects_malformed_signature() {
+    let signer = LumaSigner::new();
+    assert!(signer.recover_public("deadbeef", "48656c6c6f").is_err());
+}